@@ -0,0 +1,125 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use crate::{
+    cemantix_word::{CemantixWord, MAX_RANK},
+    words_getter::WordGetter,
+};
+
+/// number of days after which a found word counts half as much as the word of the current day
+const HALF_LIFE_DAYS: f32 = 90.0;
+
+/// relation between the score of a word and its rank (per-mille) learnt from the nearby files
+#[derive(Debug, Clone)]
+pub struct Calibration {
+    /// expected score of each rank, `scores[r]` being the score of the rank `r`
+    scores: Vec<f32>,
+    /// number of found words the calibration has been learnt from
+    pub days: usize,
+}
+
+/// interpolates linearly the scores of the ranks lying between two known ranks
+/// `points` are sorted by rank, the ranks outside of them stay unknown
+fn interpolate(points: &[(usize, f32)], size: usize) -> Vec<Option<f32>> {
+    let mut scores = vec![None; size];
+    for pair in points.windows(2) {
+        let ((r0, s0), (r1, s1)) = (pair[0], pair[1]);
+        for (r, score) in scores.iter_mut().enumerate().take(r1).skip(r0) {
+            *score = Some(s0 + (s1 - s0) * (r - r0) as f32 / (r1 - r0) as f32);
+        }
+    }
+    if let Some((r, s)) = points.last() {
+        scores[*r] = Some(*s);
+    }
+    scores
+}
+
+impl Calibration {
+    /// learns the calibration from lists of closest words whose ranks are known, along with their weight
+    /// the scores of each list are interpolated between its known ranks before being averaged
+    pub fn from_words<'a>(
+        lists: impl IntoIterator<Item = (&'a [CemantixWord], f32)>,
+    ) -> Result<Self> {
+        let size = MAX_RANK as usize + 1;
+        let (mut sums, mut weights) = (vec![0.0_f32; size], vec![0.0_f32; size]);
+        let mut days = 0;
+        for (words, weight) in lists {
+            let mut points = words
+                .iter()
+                .filter(|w| w.rank > 0 && w.is_valid())
+                .map(|w| (w.rank as usize, w.score))
+                .collect::<Vec<(usize, f32)>>();
+            if points.is_empty() {
+                continue;
+            }
+            points.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+            points.dedup_by_key(|p| p.0);
+            for (r, score) in interpolate(&points, size).into_iter().enumerate() {
+                if let Some(score) = score {
+                    sums[r] += score * weight;
+                    weights[r] += weight;
+                }
+            }
+            days += 1;
+        }
+        let points = (1..size)
+            .filter(|r| weights[*r] > 0.0)
+            .map(|r| (r, sums[r] / weights[r]))
+            .collect::<Vec<(usize, f32)>>();
+        let (Some(first), Some(last)) = (points.first().copied(), points.last().copied()) else {
+            return Err(anyhow::anyhow!(
+                "No ranked words to learn the calibration from"
+            ));
+        };
+
+        // the ranks beyond the known ones take the closest known score, scores never decrease
+        let mut scores = vec![f32::NEG_INFINITY; size];
+        let mut highest = f32::NEG_INFINITY;
+        for (r, score) in interpolate(&points, size).into_iter().enumerate().skip(1) {
+            let score = score.unwrap_or(if r < first.0 { first.1 } else { last.1 });
+            highest = highest.max(score);
+            scores[r] = highest;
+        }
+        Ok(Self { scores, days })
+    }
+
+    /// weight of a found word of `date`, the most recent days describing best the current one
+    /// found words missing from the history count as much as a word of `HALF_LIFE_DAYS` days
+    pub fn day_weight(date: Option<NaiveDate>, today: NaiveDate) -> f32 {
+        let age = date.map_or(HALF_LIFE_DAYS, |d| (today - d).num_days().max(0) as f32);
+        0.5_f32.powf(age / HALF_LIFE_DAYS)
+    }
+
+    pub fn from_words_directory(
+        words_fcontainer_name: &str,
+        history: &[(String, NaiveDate)],
+        today: NaiveDate,
+    ) -> Result<Self> {
+        let lists = WordGetter::get_all_found_word(words_fcontainer_name)?
+            .iter()
+            .filter_map(|w| {
+                let date = history.iter().find(|(h, _)| h == w).map(|(_, d)| *d);
+                WordGetter::get_cemantix_words_of_found_word(w, words_fcontainer_name)
+                    .ok()
+                    .map(|l| (l, Self::day_weight(date, today)))
+            })
+            .collect::<Vec<(Vec<CemantixWord>, f32)>>();
+        Self::from_words(lists.iter().map(|(l, weight)| (l.as_slice(), *weight)))
+    }
+
+    /// estimated rank of a score, None if the word is outside the top 1000
+    pub fn rank(&self, score: f32) -> Option<isize> {
+        let rank = self.scores.partition_point(|s| *s <= score);
+        (rank > 1).then_some(rank as isize - 1)
+    }
+
+    /// temperature displayed by the game
+    pub fn temperature(score: f32) -> f32 {
+        score * 100.0
+    }
+
+    /// checks if a score is high enough to be among the 1000 closest words
+    pub fn is_in_neighbourhood(&self, score: f32) -> bool {
+        self.rank(score).is_some()
+    }
+}
@@ -1,5 +1,8 @@
 use anyhow::Result;
-use std::hash::{Hash, Hasher};
+use std::{
+    collections::HashSet,
+    hash::{Hash, Hasher},
+};
 
 use serde::{
     de::{self, Visitor},
@@ -7,6 +10,9 @@ use serde::{
     Serializer,
 };
 
+/// highest rank (per-mille) the game gives to a word, rank 0 meaning unknown
+pub const MAX_RANK: isize = 1000;
+
 #[derive(Debug)]
 pub struct CemantixWord {
     pub word: String,
@@ -19,6 +25,30 @@ impl CemantixWord {
         Self { word, rank, score }
     }
 
+    /// checks that the score and the rank are within the bounds given by the game
+    pub fn is_valid(&self) -> bool {
+        (-1.0..=1.0).contains(&self.score) && (0..=MAX_RANK).contains(&self.rank)
+    }
+
+    /// returns true if `self` tells more than `other` about the same word (known rank first)
+    pub fn is_better_known_than(&self, other: &Self) -> bool {
+        match (self.rank > 0, other.rank > 0) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => self.score > other.score,
+        }
+    }
+
+    /// inserts `word` into `set` unless a better known version is already stored
+    pub fn insert_best_known(set: &mut HashSet<CemantixWord>, word: CemantixWord) {
+        match set.get(&word) {
+            Some(known) if !word.is_better_known_than(known) => {}
+            _ => {
+                set.replace(word);
+            }
+        }
+    }
+
     // pub fn fromTuple(data: &(String, Option<f32>)) -> Option<Self> {
     //     data.1.map(|v| Self::new(data.0.to_owned(), 0, v))
     // }
@@ -0,0 +1,219 @@
+use std::{collections::HashMap, fs::read_to_string, path::PathBuf};
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::options::{
+    lookup::DEFAULT_INDEX_FILENAME,
+    options::{
+        DEFAULT_EMBEDDINGS_FILENAME, DEFAULT_HISTORY_FILENAME, DEFAULT_SCORE_MODEL_FILENAME,
+        DEFAULT_SESSIONS_FOLDER, DEFAULT_WORDS_FOLDER,
+    },
+    pedantix::DEFAULT_PEDANTIX_HISTORY_FILENAME,
+};
+
+pub const DEFAULT_CONFIG_FILENAME: &str = "config.json";
+
+/// games of the site family, each one with its server, words and data
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Game {
+    /// French
+    #[default]
+    Cemantix,
+    /// English
+    Cemantle,
+    /// French Wikipedia pages, only played by the pedantix command
+    #[value(skip)]
+    Pedantix,
+}
+
+impl Game {
+    pub fn endpoint(&self) -> GameEndpoint {
+        GameEndpoint::new(match self {
+            Game::Cemantix | Game::Pedantix => "https://cemantix.certitudes.org",
+            Game::Cemantle => "https://cemantle.certitudes.org",
+        })
+    }
+
+    /// day of the puzzle number 1
+    pub fn epoch(&self) -> NaiveDate {
+        let (year, month, day) = match self {
+            Game::Cemantix => (2022, 3, 2),
+            Game::Cemantle => (2022, 4, 4),
+            Game::Pedantix => (2022, 4, 19),
+        };
+        NaiveDate::from_ymd_opt(year, month, day).expect("invalid epoch")
+    }
+
+    /// list of words tested by the solver when no source file is given, inside the working directory
+    /// no English list is shipped, Cemantle needs a source file
+    pub fn words_list(&self) -> Option<&'static str> {
+        match self {
+            Game::Cemantix => Some("src/francais.txt"),
+            Game::Cemantle | Game::Pedantix => None,
+        }
+    }
+
+    /// list of the most common words of the language, inside the working directory
+    pub fn common_words_list(&self) -> Option<&'static str> {
+        match self {
+            Game::Cemantix | Game::Pedantix => Some("src/most_common.txt"),
+            Game::Cemantle => None,
+        }
+    }
+
+    pub fn history_filename(&self) -> &'static str {
+        match self {
+            Game::Cemantix => DEFAULT_HISTORY_FILENAME,
+            Game::Cemantle => "cemantle_history",
+            Game::Pedantix => DEFAULT_PEDANTIX_HISTORY_FILENAME,
+        }
+    }
+
+    pub fn words_folder(&self) -> &'static str {
+        match self {
+            Game::Cemantix | Game::Pedantix => DEFAULT_WORDS_FOLDER,
+            Game::Cemantle => "cemantle_folder/",
+        }
+    }
+
+    pub fn sessions_folder(&self) -> &'static str {
+        match self {
+            Game::Cemantix | Game::Pedantix => DEFAULT_SESSIONS_FOLDER,
+            Game::Cemantle => "cemantle_sessions/",
+        }
+    }
+
+    pub fn words_index(&self) -> &'static str {
+        match self {
+            Game::Cemantix | Game::Pedantix => DEFAULT_INDEX_FILENAME,
+            Game::Cemantle => "cemantle_index",
+        }
+    }
+
+    pub fn embeddings(&self) -> &'static str {
+        match self {
+            Game::Cemantix | Game::Pedantix => DEFAULT_EMBEDDINGS_FILENAME,
+            Game::Cemantle => "cemantle_embeddings.bin",
+        }
+    }
+
+    pub fn score_model(&self) -> &'static str {
+        match self {
+            Game::Cemantix | Game::Pedantix => DEFAULT_SCORE_MODEL_FILENAME,
+            Game::Cemantle => "cemantle_score_model.json",
+        }
+    }
+}
+
+fn default_score_path() -> String {
+    String::from("/score")
+}
+
+fn default_nearby_path() -> String {
+    String::from("/nearby")
+}
+
+fn default_pedantix_path() -> String {
+    String::from("/pedantix/score")
+}
+
+/// server of the game and the paths of its endpoints
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameEndpoint {
+    pub base_url: String,
+    #[serde(default = "default_score_path")]
+    pub score_path: String,
+    #[serde(default = "default_nearby_path")]
+    pub nearby_path: String,
+    #[serde(default = "default_pedantix_path")]
+    pub pedantix_path: String,
+    /// Origin header of the requests, the base url otherwise
+    #[serde(default)]
+    pub origin: Option<String>,
+}
+
+impl Default for GameEndpoint {
+    fn default() -> Self {
+        Game::default().endpoint()
+    }
+}
+
+impl GameEndpoint {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.to_owned(),
+            score_path: default_score_path(),
+            nearby_path: default_nearby_path(),
+            pedantix_path: default_pedantix_path(),
+            origin: None,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/{}",
+            self.base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+
+    pub fn score_url(&self) -> String {
+        self.url(&self.score_path)
+    }
+
+    pub fn nearby_url(&self) -> String {
+        self.url(&self.nearby_path)
+    }
+
+    pub fn pedantix_url(&self) -> String {
+        self.url(&self.pedantix_path)
+    }
+
+    pub fn origin(&self) -> &str {
+        self.origin
+            .as_deref()
+            .unwrap_or(self.base_url.trim_end_matches('/'))
+    }
+}
+
+/// settings read from the configuration file of the working directory, overridden by the command line
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// endpoints replacing the ones of the site, by game
+    pub endpoints: HashMap<Game, GameEndpoint>,
+}
+
+impl Config {
+    /// reads the configuration file, the default configuration being used if it does not exist
+    pub fn load(filename: &str) -> Result<Self> {
+        if !PathBuf::from(filename).try_exists()? {
+            return Ok(Self::default());
+        }
+        serde_json::from_str(&read_to_string(filename)?)
+            .map_err(|e| anyhow::anyhow!("Invalid configuration file {filename} : {e}"))
+    }
+
+    pub fn endpoint(&self, game: Game) -> GameEndpoint {
+        self.endpoints
+            .get(&game)
+            .cloned()
+            .unwrap_or_else(|| game.endpoint())
+    }
+}
@@ -0,0 +1,19 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Europe::Paris;
+
+use crate::config::Game;
+
+/// day of the puzzle played at `instant`, the puzzles rolling over at midnight in Paris
+pub fn puzzle_date_at<Tz: TimeZone>(instant: &DateTime<Tz>) -> NaiveDate {
+    instant.with_timezone(&Paris).date_naive()
+}
+
+/// day of the current puzzle, whatever the time zone of the machine
+pub fn puzzle_date() -> NaiveDate {
+    puzzle_date_at(&Utc::now())
+}
+
+/// number of the puzzle of a day in a game
+pub fn puzzle_number(game: Game, date: NaiveDate) -> i64 {
+    (date - game.epoch()).num_days() + 1
+}
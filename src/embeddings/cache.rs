@@ -0,0 +1,228 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufWriter, Write},
+};
+
+use anyhow::Result;
+use memmap2::Mmap;
+
+use super::loader::{dot, Embeddings};
+
+const MAGIC: &[u8; 8] = b"CMXEMB01";
+/// magic, dimension, number of words, quantisation flag, size of the vocabulary
+const HEADER_SIZE: usize = 8 + 4 + 4 + 4 + 4;
+
+/// memory-mapped binary file of embeddings built by the `embeddings build` command
+///
+/// layout (little-endian): header, the words separated by new lines padded to 4 bytes,
+/// then either the f32 matrix or a f32 scale per word followed by the i8 matrix
+pub struct EmbeddingsCache {
+    pub dim: usize,
+    pub quantized: bool,
+    pub words: Vec<String>,
+    indexes: HashMap<String, usize>,
+    /// offset of the scales if quantised, of the matrix otherwise
+    offset: usize,
+    mmap: Mmap,
+}
+
+impl EmbeddingsCache {
+    /// writes the embeddings, quantising each vector to i8 with its own scale if asked
+    /// the file is written next to `filename` then renamed over it, as it may be mapped by another process
+    pub fn write(embeddings: &Embeddings, filename: &str, quantize: bool) -> Result<()> {
+        let temporary = format!("{filename}.tmp");
+        let mut file = BufWriter::new(File::create(&temporary)?);
+        Self::write_into(embeddings, &mut file, quantize)?;
+        file.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&temporary, filename)?;
+        Ok(())
+    }
+
+    fn write_into(embeddings: &Embeddings, file: &mut impl Write, quantize: bool) -> Result<()> {
+        let mut vocabulary = embeddings.words.join("\n").into_bytes();
+        vocabulary.resize(vocabulary.len().next_multiple_of(4), 0);
+
+        file.write_all(MAGIC)?;
+        file.write_all(&(embeddings.dim as u32).to_le_bytes())?;
+        file.write_all(&(embeddings.len() as u32).to_le_bytes())?;
+        file.write_all(&(quantize as u32).to_le_bytes())?;
+        file.write_all(&(vocabulary.len() as u32).to_le_bytes())?;
+        file.write_all(&vocabulary)?;
+        if quantize {
+            let scales = (0..embeddings.len())
+                .map(|i| {
+                    embeddings
+                        .vector(i)
+                        .iter()
+                        .fold(0.0_f32, |m, v| m.max(v.abs()))
+                        / 127.0
+                })
+                .collect::<Vec<f32>>();
+            for scale in scales.iter() {
+                file.write_all(&scale.to_le_bytes())?;
+            }
+            for (i, scale) in scales.iter().enumerate() {
+                let scale = if *scale > 0.0 { *scale } else { 1.0 };
+                let row = embeddings
+                    .vector(i)
+                    .iter()
+                    .map(|v| (v / scale).round() as i8 as u8)
+                    .collect::<Vec<u8>>();
+                file.write_all(&row)?;
+            }
+        } else {
+            for v in embeddings.data.iter() {
+                file.write_all(&v.to_le_bytes())?;
+            }
+        }
+        file.flush()?;
+        Ok(())
+    }
+
+    pub fn open(filename: &str) -> Result<Self> {
+        let file = File::open(filename)?;
+        // the file is only read and is replaced as a whole by the build command
+        let mmap = unsafe { Mmap::map(&file)? };
+        if mmap.len() < HEADER_SIZE || &mmap[..8] != MAGIC {
+            return Err(anyhow::anyhow!("{filename} is not an embeddings file"));
+        }
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes([
+                mmap[offset],
+                mmap[offset + 1],
+                mmap[offset + 2],
+                mmap[offset + 3],
+            ]) as usize
+        };
+        let (dim, count) = (read_u32(8), read_u32(12));
+        let quantized = read_u32(16) != 0;
+        let offset = HEADER_SIZE + read_u32(20);
+        // the rows are read in place as f32
+        if !offset.is_multiple_of(4)
+            || mmap.as_ptr().align_offset(4) != 0
+            || cfg!(target_endian = "big")
+        {
+            return Err(anyhow::anyhow!(
+                "{filename} cannot be read in place on this machine"
+            ));
+        }
+        let expected = offset
+            + if quantized {
+                count * 4 + count * dim
+            } else {
+                count * dim * 4
+            };
+        if mmap.len() != expected {
+            return Err(anyhow::anyhow!(
+                "{filename} is truncated : {} bytes instead of {expected}",
+                mmap.len()
+            ));
+        }
+
+        let words = std::str::from_utf8(&mmap[HEADER_SIZE..offset])?
+            .trim_end_matches('\0')
+            .split('\n')
+            .filter(|w| count > 0 || !w.is_empty())
+            .map(String::from)
+            .collect::<Vec<String>>();
+        if words.len() != count {
+            return Err(anyhow::anyhow!(
+                "{filename} contains {} words instead of {count}",
+                words.len()
+            ));
+        }
+        let indexes = words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (w.to_owned(), i))
+            .collect();
+        Ok(Self {
+            dim,
+            quantized,
+            words,
+            indexes,
+            offset,
+            mmap,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn index(&self, word: &str) -> Option<usize> {
+        self.indexes.get(word).copied()
+    }
+
+    /// f32 values stored from `start` in the mmap, viewed in place
+    fn floats(&self, start: usize, len: usize) -> &[f32] {
+        // the offsets are multiples of 4 from the start of the mmap, checked to be aligned by open
+        let (prefix, floats, _) = unsafe { self.mmap[start..start + len * 4].align_to::<f32>() };
+        assert!(
+            prefix.is_empty() && floats.len() == len,
+            "misaligned embeddings row"
+        );
+        floats
+    }
+
+    /// scale of the quantised row of the word `i`
+    fn scale(&self, i: usize) -> f32 {
+        self.floats(self.offset + i * 4, 1)[0]
+    }
+
+    /// row of the word `i` if the embeddings are not quantised
+    pub fn row_f32(&self, i: usize) -> &[f32] {
+        self.floats(self.offset + i * self.dim * 4, self.dim)
+    }
+
+    /// quantised row of the word `i`, to multiply by its scale
+    pub fn row_i8(&self, i: usize) -> &[i8] {
+        let start = self.offset + self.len() * 4 + i * self.dim;
+        let (_, row, _) = unsafe { self.mmap[start..start + self.dim].align_to::<i8>() };
+        row
+    }
+
+    /// normalised vector of the word `i`, dequantised if needed
+    pub fn vector(&self, i: usize) -> Vec<f32> {
+        if self.quantized {
+            let scale = self.scale(i);
+            self.row_i8(i).iter().map(|v| *v as f32 * scale).collect()
+        } else {
+            self.row_f32(i).to_vec()
+        }
+    }
+
+    /// cosine similarity between a normalised vector and the word `i`
+    pub fn similarity_to(&self, vector: &[f32], i: usize) -> f32 {
+        if self.quantized {
+            let row = self.row_i8(i);
+            vector
+                .iter()
+                .zip(row)
+                .map(|(a, b)| a * *b as f32)
+                .sum::<f32>()
+                * self.scale(i)
+        } else {
+            dot(vector, self.row_f32(i))
+        }
+    }
+
+    pub fn similarity(&self, a: usize, b: usize) -> f32 {
+        if self.quantized {
+            let dot = self
+                .row_i8(a)
+                .iter()
+                .zip(self.row_i8(b))
+                .map(|(x, y)| *x as i32 * *y as i32)
+                .sum::<i32>();
+            dot as f32 * self.scale(a) * self.scale(b)
+        } else {
+            dot(self.row_f32(a), self.row_f32(b))
+        }
+    }
+}
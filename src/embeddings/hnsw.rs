@@ -0,0 +1,281 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashSet},
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+};
+
+use anyhow::Result;
+
+const MAGIC: &[u8; 8] = b"CMXHNSW1";
+const NONE: u32 = u32::MAX;
+
+/// node reached during a search, ordered by similarity to the query
+#[derive(Clone, Copy, Debug)]
+struct Candidate {
+    similarity: f32,
+    id: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.similarity
+            .total_cmp(&other.similarity)
+            .then(other.id.cmp(&self.id))
+    }
+}
+
+/// hierarchical navigable small world graph over normalised vectors, searched by cosine similarity
+#[derive(Debug, Default)]
+pub struct Hnsw {
+    /// maximum number of neighbours of a node on the upper layers, twice on the layer 0
+    pub m: usize,
+    entry: Option<usize>,
+    /// neighbours of each node on every layer from 0 to the level of the node
+    layers: Vec<Vec<Vec<u32>>>,
+    /// size and modification time of the embeddings file the graph was built from
+    pub fingerprint: (u64, u64),
+}
+
+impl Hnsw {
+    /// builds the graph of `count` vectors given the similarity between two of them
+    pub fn build(
+        count: usize,
+        similarity: impl Fn(usize, usize) -> f32,
+        m: usize,
+        ef_construction: usize,
+    ) -> Self {
+        let mut hnsw = Self {
+            m,
+            ..Default::default()
+        };
+        for i in 0..count {
+            hnsw.insert(i, |j| similarity(i, j), &similarity, ef_construction);
+        }
+        hnsw
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// deterministic level of a node, geometrically distributed
+    fn level(&self, id: usize) -> usize {
+        // splitmix64
+        let mut z = (id as u64).wrapping_add(0x9e3779b97f4a7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^= z >> 31;
+        let uniform = ((z >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+        (-uniform.ln() / (self.m.max(2) as f64).ln()) as usize
+    }
+
+    fn insert(
+        &mut self,
+        id: usize,
+        query: impl Fn(usize) -> f32,
+        similarity: impl Fn(usize, usize) -> f32,
+        ef_construction: usize,
+    ) {
+        let level = self.level(id);
+        self.layers.push(vec![Vec::new(); level + 1]);
+        let Some(entry) = self.entry else {
+            self.entry = Some(id);
+            return;
+        };
+        let top = self.layers[entry].len() - 1;
+
+        let mut entries = vec![Candidate {
+            similarity: query(entry),
+            id: entry,
+        }];
+        for layer in (level + 1..=top).rev() {
+            entries = self.search_layer(&query, entries, 1, layer);
+        }
+        for layer in (0..=level.min(top)).rev() {
+            entries = self.search_layer(&query, entries, ef_construction, layer);
+            let max = if layer == 0 { 2 * self.m } else { self.m };
+            for c in entries.iter().take(self.m) {
+                self.layers[id][layer].push(c.id as u32);
+                let neighbours = &mut self.layers[c.id][layer];
+                neighbours.push(id as u32);
+                if neighbours.len() > max {
+                    // keeps the closest neighbours of the node
+                    let mut sorted = neighbours
+                        .iter()
+                        .map(|n| (similarity(c.id, *n as usize), *n))
+                        .collect::<Vec<(f32, u32)>>();
+                    sorted.sort_by(|a, b| b.0.total_cmp(&a.0));
+                    *neighbours = sorted.into_iter().take(max).map(|(_, n)| n).collect();
+                }
+            }
+        }
+        if level > top {
+            self.entry = Some(id);
+        }
+    }
+
+    /// best first search on a layer, returns at most `ef` nodes sorted by decreasing similarity
+    fn search_layer(
+        &self,
+        query: &impl Fn(usize) -> f32,
+        entries: Vec<Candidate>,
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited = entries.iter().map(|c| c.id).collect::<HashSet<usize>>();
+        let mut candidates = entries.iter().copied().collect::<BinaryHeap<Candidate>>();
+        let mut results = entries
+            .into_iter()
+            .map(Reverse)
+            .collect::<BinaryHeap<Reverse<Candidate>>>();
+        while results.len() > ef {
+            results.pop();
+        }
+
+        while let Some(candidate) = candidates.pop() {
+            let worst = results.peek().map(|r| r.0.similarity);
+            if results.len() >= ef && worst.is_some_and(|w| candidate.similarity < w) {
+                break;
+            }
+            for n in self.layers[candidate.id][layer].iter() {
+                let n = *n as usize;
+                if !visited.insert(n) {
+                    continue;
+                }
+                let c = Candidate {
+                    similarity: query(n),
+                    id: n,
+                };
+                if results.len() < ef || results.peek().is_some_and(|w| c > w.0) {
+                    candidates.push(c);
+                    results.push(Reverse(c));
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+        let mut results = results.into_iter().map(|r| r.0).collect::<Vec<Candidate>>();
+        results.sort_by(|a, b| b.cmp(a));
+        results
+    }
+
+    /// approximate `k` most similar nodes to the query, `query(i)` being the similarity to the node `i`
+    pub fn search(&self, query: impl Fn(usize) -> f32, k: usize, ef: usize) -> Vec<(usize, f32)> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        let mut entries = vec![Candidate {
+            similarity: query(entry),
+            id: entry,
+        }];
+        for layer in (1..self.layers[entry].len()).rev() {
+            entries = self.search_layer(&query, entries, 1, layer);
+        }
+        self.search_layer(&query, entries, ef.max(k), 0)
+            .into_iter()
+            .take(k)
+            .map(|c| (c.id, c.similarity))
+            .collect()
+    }
+
+    pub fn save(&self, filename: &str) -> Result<()> {
+        let mut file = BufWriter::new(File::create(filename)?);
+        file.write_all(MAGIC)?;
+        file.write_all(&self.fingerprint.0.to_le_bytes())?;
+        file.write_all(&self.fingerprint.1.to_le_bytes())?;
+        file.write_all(&(self.m as u32).to_le_bytes())?;
+        file.write_all(&self.entry.map_or(NONE, |e| e as u32).to_le_bytes())?;
+        file.write_all(&(self.layers.len() as u32).to_le_bytes())?;
+        for node in self.layers.iter() {
+            file.write_all(&(node.len() as u32).to_le_bytes())?;
+            for neighbours in node.iter() {
+                file.write_all(&(neighbours.len() as u32).to_le_bytes())?;
+                for n in neighbours.iter() {
+                    file.write_all(&n.to_le_bytes())?;
+                }
+            }
+        }
+        file.flush()?;
+        Ok(())
+    }
+
+    pub fn load(filename: &str) -> Result<Self> {
+        let file = File::open(filename)?;
+        let size = file.metadata()?.len() as usize;
+        let mut file = BufReader::new(file);
+        let mut magic = [0_u8; 8];
+        file.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(anyhow::anyhow!("{filename} is not an index of embeddings"));
+        }
+        let fingerprint = (read_u64(&mut file)?, read_u64(&mut file)?);
+        let m = read_u32(&mut file)? as usize;
+        let entry = Some(read_u32(&mut file)?)
+            .filter(|e| *e != NONE)
+            .map(|e| e as usize);
+        let count = read_u32(&mut file)? as usize;
+        // each node takes at least 4 bytes
+        let mut layers = Vec::with_capacity(count.min(size / 4));
+        for _ in 0..count {
+            let mut node = Vec::new();
+            for _ in 0..read_u32(&mut file)? {
+                let neighbours = (0..read_u32(&mut file)?)
+                    .map(|_| read_u32(&mut file))
+                    .collect::<Result<Vec<u32>>>()?;
+                node.push(neighbours);
+            }
+            layers.push(node);
+        }
+        // the nodes reached by a search must exist on the layers they are reached from
+        let valid = layers.iter().all(|node| !node.is_empty())
+            && entry.map_or(count == 0, |e| e < count)
+            && layers.iter().all(|node| {
+                node.iter().enumerate().all(|(layer, neighbours)| {
+                    neighbours
+                        .iter()
+                        .all(|n| layers.get(*n as usize).is_some_and(|l| l.len() > layer))
+                })
+            });
+        if !valid {
+            return Err(anyhow::anyhow!("{filename} is corrupted"));
+        }
+        Ok(Self {
+            m,
+            entry,
+            layers,
+            fingerprint,
+        })
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32> {
+    let mut bytes = [0_u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64> {
+    let mut bytes = [0_u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
@@ -0,0 +1,191 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use anyhow::Result;
+
+/// formats of the files of vectors
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, clap::ValueEnum)]
+pub enum EmbeddingsFormat {
+    /// word2vec text format, also used by the fastText .vec files
+    Text,
+    /// word2vec binary format
+    Binary,
+}
+
+impl EmbeddingsFormat {
+    /// guesses the format from the extension of the file
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some("bin") => Self::Binary,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// normalised vectors of words stored in a contiguous matrix, a row per word
+#[derive(Debug, Default)]
+pub struct Embeddings {
+    pub dim: usize,
+    pub words: Vec<String>,
+    /// row-major matrix of `words.len()` rows of `dim` values
+    pub data: Vec<f32>,
+    indexes: HashMap<String, usize>,
+}
+
+impl Embeddings {
+    pub fn new(dim: usize) -> Self {
+        Self {
+            dim,
+            ..Default::default()
+        }
+    }
+
+    /// loads a file of vectors, keeping only the words of `vocabulary` if given
+    pub fn load(
+        path: &str,
+        format: Option<EmbeddingsFormat>,
+        vocabulary: Option<&HashSet<String>>,
+    ) -> Result<Self> {
+        let reader = BufReader::with_capacity(1 << 20, File::open(path)?);
+        match format.unwrap_or_else(|| EmbeddingsFormat::from_path(path)) {
+            EmbeddingsFormat::Text => Self::load_text(reader, vocabulary),
+            EmbeddingsFormat::Binary => Self::load_binary(reader, vocabulary),
+        }
+    }
+
+    /// parses the text format: an optional "count dim" header then a word and its values per line
+    /// the values of the words missing from `vocabulary` are not parsed
+    pub fn load_text<R: BufRead>(reader: R, vocabulary: Option<&HashSet<String>>) -> Result<Self> {
+        let mut embeddings = Self::new(0);
+        let mut vector = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let mut parts = line.split_whitespace();
+            let Some(word) = parts.next() else {
+                continue;
+            };
+            let header = (i == 0 && parts.clone().count() == 1).then(|| {
+                (
+                    word.parse::<usize>(),
+                    parts.clone().next().map(str::parse::<usize>),
+                )
+            });
+            if let Some((Ok(_), Some(Ok(dim)))) = header {
+                embeddings.dim = dim;
+                continue;
+            }
+            if vocabulary.is_some_and(|v| !v.contains(word)) {
+                continue;
+            }
+            vector.clear();
+            for v in parts {
+                vector.push(
+                    v.parse::<f32>()
+                        .map_err(|e| anyhow::anyhow!("line {} : {e}", i + 1))?,
+                );
+            }
+            if embeddings.dim == 0 {
+                embeddings.dim = vector.len();
+            }
+            if vector.len() != embeddings.dim {
+                return Err(anyhow::anyhow!(
+                    "line {} : {} values instead of {}",
+                    i + 1,
+                    vector.len(),
+                    embeddings.dim
+                ));
+            }
+            embeddings.push(word, &vector);
+        }
+        Ok(embeddings)
+    }
+
+    /// parses the binary format: a "count dim" header line then, for each word,
+    /// the word followed by a space and `dim` little-endian f32
+    pub fn load_binary<R: BufRead>(
+        mut reader: R,
+        vocabulary: Option<&HashSet<String>>,
+    ) -> Result<Self> {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        let mut header = header.split_whitespace().map(|v| v.parse::<usize>());
+        let (Some(Ok(count)), Some(Ok(dim))) = (header.next(), header.next()) else {
+            return Err(anyhow::anyhow!("invalid header"));
+        };
+
+        let mut embeddings = Self::new(dim);
+        let mut word = Vec::new();
+        let mut bytes = vec![0_u8; dim * 4];
+        let mut vector = vec![0.0_f32; dim];
+        for _ in 0..count {
+            word.clear();
+            reader.read_until(b' ', &mut word)?;
+            word.pop();
+            // the previous vector may be followed by a new line
+            let start = word.iter().position(|b| *b != b'\n').unwrap_or(word.len());
+            let w = String::from_utf8_lossy(&word[start..]).to_string();
+            reader.read_exact(&mut bytes)?;
+            for (v, b) in vector.iter_mut().zip(bytes.chunks_exact(4)) {
+                *v = f32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+            }
+            if vocabulary.is_none_or(|v| v.contains(&w)) {
+                embeddings.push(&w, &vector);
+            }
+        }
+        Ok(embeddings)
+    }
+
+    /// adds a word and its normalised vector, the first vector of a word is kept
+    pub fn push(&mut self, word: &str, vector: &[f32]) {
+        if self.indexes.contains_key(word) {
+            return;
+        }
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        let norm = if norm > 0.0 { norm } else { 1.0 };
+        self.data.extend(vector.iter().map(|v| v / norm));
+        self.indexes.insert(word.to_owned(), self.words.len());
+        self.words.push(word.to_owned());
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn index(&self, word: &str) -> Option<usize> {
+        self.indexes.get(word).copied()
+    }
+
+    pub fn vector(&self, i: usize) -> &[f32] {
+        &self.data[i * self.dim..(i + 1) * self.dim]
+    }
+
+    /// cosine similarity of two words, vectors being normalised
+    pub fn similarity(&self, a: usize, b: usize) -> f32 {
+        dot(self.vector(a), self.vector(b))
+    }
+}
+
+pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+/// reads a words list, a word at each line
+pub fn read_vocabulary(path: &str) -> Result<HashSet<String>> {
+    let mut vocabulary = HashSet::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        let word = line.trim();
+        if !word.is_empty() {
+            vocabulary.insert(word.to_owned());
+        }
+    }
+    Ok(vocabulary)
+}
@@ -0,0 +1,82 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::cache::EmbeddingsCache;
+
+/// errors of a model on a set of found words
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FitErrors {
+    pub rmse: f32,
+    pub mae: f32,
+}
+
+/// mapping from the cosine similarity of the embeddings to the score given by the game,
+/// learnt by the calibrate command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreModel {
+    /// coefficients of the polynomial, from the constant term
+    pub coefficients: Vec<f64>,
+    /// errors on the found words the polynomial has been fitted to
+    #[serde(default)]
+    pub training: FitErrors,
+    /// errors on the found words kept out of the fit, None if every word was used
+    #[serde(default)]
+    pub held_out: Option<FitErrors>,
+    /// number of found words the model has been learnt from
+    pub days: usize,
+}
+
+impl ScoreModel {
+    pub fn apply(&self, similarity: f32) -> f32 {
+        let score = self
+            .coefficients
+            .iter()
+            .rev()
+            .fold(0.0_f64, |acc, c| acc * similarity as f64 + c);
+        (score as f32).clamp(-1.0, 1.0)
+    }
+
+    pub fn load(filename: &str) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(filename)?)?)
+    }
+
+    pub fn save(&self, filename: &str) -> Result<()> {
+        Ok(fs::write(filename, serde_json::to_string_pretty(self)?)?)
+    }
+}
+
+/// scores words without the game, from the embeddings and the learnt model if any
+pub struct OfflineScorer {
+    pub cache: EmbeddingsCache,
+    pub model: Option<ScoreModel>,
+}
+
+impl OfflineScorer {
+    pub fn new(cache: EmbeddingsCache, model: Option<ScoreModel>) -> Self {
+        Self { cache, model }
+    }
+
+    /// opens the embeddings, the model being used only if its file exists
+    pub fn open(embeddings_filename: &str, model_filename: &str) -> Result<Self> {
+        let cache = EmbeddingsCache::open(embeddings_filename)?;
+        let model = if PathBuf::from(model_filename).try_exists()? {
+            Some(ScoreModel::load(model_filename)?)
+        } else {
+            None
+        };
+        Ok(Self::new(cache, model))
+    }
+
+    /// estimated score of `word` when `answer` is the word of the day, None if a word is unknown
+    pub fn score(&self, answer: &str, word: &str) -> Option<f32> {
+        let similarity = self
+            .cache
+            .similarity(self.cache.index(answer)?, self.cache.index(word)?);
+        Some(match self.model.as_ref() {
+            Some(model) => model.apply(similarity),
+            None => similarity,
+        })
+    }
+}
@@ -1,12 +1,44 @@
+pub mod calibration;
 pub mod cemantix_word;
+pub mod config;
+pub mod day;
+pub mod embeddings {
+    pub mod cache;
+    pub mod hnsw;
+    pub mod loader;
+    pub mod scorer;
+}
+pub mod pedantix;
+pub mod puzzle;
+pub mod semantic_graph;
+pub mod session;
+pub mod stats;
 pub mod utils;
 pub mod words_getter;
 pub mod options {
+    pub mod analyze;
+    pub mod calibrate;
+    pub mod check;
+    pub mod clusters;
+    pub mod diff;
+    pub mod embeddings;
+    pub mod export;
     pub mod extend;
     pub mod graph;
+    pub mod import;
+    pub mod lookup;
+    pub mod map;
+    pub mod merge;
     pub mod nearby;
+    pub mod neighbors;
+    pub mod opening_book;
     pub mod options;
+    pub mod path;
+    pub mod pedantix;
+    pub mod play;
     pub mod remove_useless_words;
+    pub mod report;
+    pub mod serve;
     pub mod solve;
     pub mod sort;
 }
@@ -14,9 +46,40 @@ pub const HISTORY_FORMAT: &str = "%d-%m-%Y";
 
 #[cfg(test)]
 mod test {
-    use std::cmp::Ordering;
+    use std::{cmp::Ordering, path::PathBuf};
+
+    use clap::Parser;
 
-    use crate::cemantix_word::CemantixWord;
+    use crate::{
+        calibration::Calibration,
+        cemantix_word::{CemantixWord, MAX_RANK},
+        config::{Config, Game},
+        day::{puzzle_date_at, puzzle_number},
+        embeddings::{
+            cache::EmbeddingsCache,
+            hnsw::Hnsw,
+            loader::Embeddings,
+            scorer::{FitErrors, OfflineScorer, ScoreModel},
+        },
+        options::{
+            analyze::Distribution,
+            check::{Check, Issue},
+            diff::Diff,
+            export::{Export, Manifest, ManifestEntry, MANIFEST_NAME},
+            import::Import,
+            map::Map,
+            nearby::Nearby,
+            options::Cli,
+            report::href,
+            serve::{Serve, MAX_BODY_SIZE, MAX_LINE_SIZE},
+        },
+        pedantix::{Article, PedantixResponse, Reveal},
+        puzzle::Puzzle,
+        semantic_graph::SemanticGraph,
+        session::{Guess, Session},
+        stats::{errors, histogram, jaccard, kendall, percentile, polyfit, spearman},
+        words_getter::WordGetter,
+    };
 
     #[test]
     fn cemantix_word_order() {
@@ -41,4 +104,978 @@ mod test {
 
         assert_ne!(w1, w2)
     }
+
+    #[test]
+    fn nearby_parsing() {
+        let words = Nearby::parse_nearby(r#"[["b",999,0.8],["a",1000,0.9],["c",12,0.3]]"#).unwrap();
+        assert_eq!(
+            words.iter().map(|w| w.word.as_str()).collect::<Vec<&str>>(),
+            vec!["c", "b", "a"]
+        );
+
+        assert!(Nearby::parse_nearby("<html>502 Bad Gateway</html>").is_err());
+        assert!(Nearby::parse_nearby(r#"{"error": "unknown word"}"#).is_err());
+        assert!(Nearby::parse_nearby("[]").is_err());
+        assert!(Nearby::parse_nearby(r#"[["b",999,0.95],["a",1000,0.9]]"#).is_err());
+    }
+
+    #[test]
+    fn semantic_graph_shortest_path() {
+        let mut graph = SemanticGraph::new();
+        graph.add_edge("chat", "chien", 0.2, 0);
+        graph.add_edge("chat", "souris", 0.7, 0);
+        graph.add_edge("chien", "souris", 0.6, 0);
+        graph.add_edge("loup", "ours", 0.5, 0);
+
+        let path = graph
+            .shortest_path(graph.index("chat").unwrap(), graph.index("chien").unwrap())
+            .unwrap();
+        assert_eq!(
+            path.iter()
+                .map(|(n, _)| graph.nodes[*n].as_str())
+                .collect::<Vec<&str>>(),
+            vec!["chat", "souris", "chien"]
+        );
+        assert_eq!(path[2].1, 0.6);
+
+        assert!(graph
+            .shortest_path(graph.index("chat").unwrap(), graph.index("ours").unwrap())
+            .is_none());
+    }
+
+    #[test]
+    fn stats() {
+        assert_eq!(jaccard(&[1, 2, 3], &[2, 3, 4]), 0.5);
+        assert_eq!(jaccard::<u8>(&[], &[]), 0.0);
+
+        let sorted = [0.1, 0.2, 0.3, 0.4, 0.5];
+        assert_eq!(percentile(&sorted, 0.0), 0.1);
+        assert_eq!(percentile(&sorted, 50.0), 0.3);
+        assert!((percentile(&sorted, 90.0) - 0.46).abs() < 1e-6);
+
+        assert_eq!(histogram(&[0.0, 0.3, 0.6, 1.0], 2, 0.0, 1.0), vec![2, 2]);
+
+        let xs = [0.1, 0.2, 0.3, 0.4];
+        assert_eq!(spearman(&xs, &[1.0, 2.0, 3.0, 10.0]), Some(1.0));
+        assert_eq!(kendall(&xs, &[4.0, 3.0, 2.0, 1.0]), Some(-1.0));
+        assert_eq!(spearman(&xs, &[1.0, 1.0, 1.0, 1.0]), None);
+    }
+
+    #[test]
+    fn calibration_rank() {
+        let day1 = vec![
+            CemantixWord::new(String::from("a"), 1, 0.30),
+            CemantixWord::new(String::from("b"), 500, 0.40),
+            CemantixWord::new(String::from("c"), 1000, 1.0),
+        ];
+        let day2 = vec![
+            CemantixWord::new(String::from("d"), 1, 0.20),
+            CemantixWord::new(String::from("e"), 500, 0.50),
+        ];
+        let calibration =
+            Calibration::from_words([(day1.as_slice(), 1.0), (day2.as_slice(), 1.0)]).unwrap();
+
+        assert_eq!(calibration.days, 2);
+        assert_eq!(calibration.rank(0.1), None);
+        assert_eq!(calibration.rank(0.25), Some(1));
+        // interpolated between the known ranks of each day
+        assert_eq!(calibration.rank(0.35), Some(250));
+        assert_eq!(calibration.rank(0.45), Some(541));
+        assert_eq!(calibration.rank(0.71), Some(758));
+        assert_eq!(calibration.rank(1.0), Some(1000));
+        assert!(calibration.is_in_neighbourhood(0.99));
+        assert!(Calibration::from_words([([].as_slice(), 1.0)]).is_err());
+
+        // the recent days weigh more
+        let today = date("01-07-2024");
+        assert_eq!(Calibration::day_weight(Some(today), today), 1.0);
+        let old = Calibration::day_weight(Some(date("01-01-2024")), today);
+        assert!(old < 0.5 && old > 0.0);
+        let calibration =
+            Calibration::from_words([(day1.as_slice(), 1.0), (day2.as_slice(), 3.0)]).unwrap();
+        assert_eq!(calibration.rank(0.2252), Some(1));
+        assert_eq!(calibration.rank(0.224), None);
+    }
+
+    #[test]
+    fn embeddings_loading() {
+        let vocabulary = ["chat", "chien"].map(String::from).into();
+        // the values of the words left out are not read
+        let text = "3 2\nchat 3 4\nmaison 1 x\nchien 0 2\n";
+        let embeddings = Embeddings::load_text(text.as_bytes(), Some(&vocabulary)).unwrap();
+        assert_eq!(embeddings.dim, 2);
+        assert_eq!(embeddings.words, vec!["chat", "chien"]);
+        assert_eq!(embeddings.vector(0), &[0.6, 0.8]);
+        assert_eq!(embeddings.index("maison"), None);
+        assert!((embeddings.similarity(0, 1) - 0.8).abs() < 1e-6);
+        assert!(Embeddings::load_text(text.as_bytes(), None).is_err());
+        let embeddings = Embeddings::load_text("1 0.5\n2 0.1\n".as_bytes(), None).unwrap();
+        assert_eq!((embeddings.dim, embeddings.len()), (1, 2));
+
+        let mut binary = b"2 2\n".to_vec();
+        for (word, vector) in [("chat", [3.0_f32, 4.0]), ("chien", [0.0, 2.0])] {
+            binary.extend(word.as_bytes());
+            binary.push(b' ');
+            vector.iter().for_each(|v| binary.extend(v.to_le_bytes()));
+            binary.push(b'\n');
+        }
+        let embeddings = Embeddings::load_binary(binary.as_slice(), None).unwrap();
+        assert_eq!(embeddings.words, vec!["chat", "chien"]);
+        assert_eq!(embeddings.vector(1), &[0.0, 1.0]);
+    }
+
+    #[test]
+    fn embeddings_cache() {
+        let mut embeddings = Embeddings::new(3);
+        embeddings.push("chat", &[1.0, 2.0, 2.0]);
+        embeddings.push("chien", &[0.0, -3.0, 4.0]);
+        let filename = std::env::temp_dir().join("cemantix_embeddings_cache.bin");
+        let filename = filename.to_str().unwrap();
+        for quantize in [false, true] {
+            // the cache opened before is replaced, not rewritten under its mapping
+            let previous = EmbeddingsCache::open(filename).ok();
+            EmbeddingsCache::write(&embeddings, filename, quantize).unwrap();
+            assert!(!PathBuf::from(format!("{filename}.tmp")).exists());
+            if let Some(previous) = previous {
+                assert_eq!(previous.vector(1).len(), 3);
+            }
+            let cache = EmbeddingsCache::open(filename).unwrap();
+            assert_eq!(cache.len(), 2);
+            assert_eq!(cache.index("chien"), Some(1));
+            let tolerance = if quantize { 1e-2 } else { 1e-6 };
+            for (a, b) in cache.vector(0).iter().zip(embeddings.vector(0)) {
+                assert!((a - b).abs() < tolerance);
+            }
+            assert!((cache.similarity(0, 1) - embeddings.similarity(0, 1)).abs() < tolerance);
+            let similarity = cache.similarity_to(embeddings.vector(1), 0);
+            assert!((similarity - embeddings.similarity(0, 1)).abs() < tolerance);
+            if quantize {
+                assert_eq!(cache.row_i8(0).len(), 3);
+            } else {
+                assert_eq!(cache.row_f32(1), embeddings.vector(1));
+            }
+        }
+        std::fs::remove_file(filename).unwrap();
+    }
+
+    #[test]
+    fn hnsw_search() {
+        // deterministic pseudo-random vectors
+        let (dim, count) = (8, 500);
+        let mut seed = 42_u64;
+        let mut embeddings = Embeddings::new(dim);
+        for i in 0..count {
+            let vector = (0..dim)
+                .map(|_| {
+                    seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    (seed >> 33) as f32 / (1u64 << 31) as f32 - 0.5
+                })
+                .collect::<Vec<f32>>();
+            embeddings.push(&i.to_string(), &vector);
+        }
+        let hnsw = Hnsw::build(count, |a, b| embeddings.similarity(a, b), 8, 50);
+        assert_eq!(hnsw.len(), count);
+
+        let k = 10;
+        let mut found = 0;
+        for query in (0..count).step_by(50) {
+            let mut exact = (0..count)
+                .map(|i| (i, embeddings.similarity(query, i)))
+                .collect::<Vec<(usize, f32)>>();
+            exact.sort_by(|a, b| b.1.total_cmp(&a.1));
+            let result = hnsw.search(|i| embeddings.similarity(query, i), k, 50);
+            assert_eq!(result[0].0, query);
+            found += result
+                .iter()
+                .filter(|(i, _)| exact.iter().take(k).any(|(j, _)| i == j))
+                .count();
+        }
+        assert!(found as f32 / (k * count / 50) as f32 > 0.9);
+
+        let filename = std::env::temp_dir().join("cemantix_embeddings.hnsw");
+        let filename = filename.to_str().unwrap();
+        hnsw.save(filename).unwrap();
+        let loaded = Hnsw::load(filename).unwrap();
+        assert_eq!(
+            loaded.search(|i| embeddings.similarity(7, i), k, 50),
+            hnsw.search(|i| embeddings.similarity(7, i), k, 50)
+        );
+        // a damaged index is refused instead of making the search panic
+        let bytes = std::fs::read(filename).unwrap();
+        std::fs::write(filename, &bytes[..bytes.len() / 2]).unwrap();
+        assert!(Hnsw::load(filename).is_err());
+        let mut damaged = bytes.clone();
+        damaged[28..32].copy_from_slice(&(count as u32).to_le_bytes());
+        std::fs::write(filename, &damaged).unwrap();
+        assert!(Hnsw::load(filename).is_err());
+        let mut damaged = bytes.clone();
+        damaged[32..36].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(filename, &damaged).unwrap();
+        assert!(Hnsw::load(filename).is_err());
+        std::fs::remove_file(filename).unwrap();
+    }
+
+    #[test]
+    fn score_model_fit() {
+        let xs = [0.0, 0.1, 0.2, 0.4, 0.5, 0.7, 0.9, 1.0];
+        let ys = xs.map(|x| 0.1 - 0.5 * x + 1.4 * x * x);
+        let coefficients = polyfit(&xs, &ys, 2).unwrap();
+        for (c, expected) in coefficients.iter().zip([0.1, -0.5, 1.4]) {
+            assert!((c - expected).abs() < 1e-4);
+        }
+        assert!(polyfit(&[0.5, 0.5], &[0.1, 0.2], 1).is_none());
+
+        let model = ScoreModel {
+            coefficients,
+            training: FitErrors::default(),
+            held_out: Some(FitErrors {
+                rmse: 0.1,
+                mae: 0.05,
+            }),
+            days: 1,
+        };
+        assert!((model.apply(0.5) - 0.2).abs() < 1e-4);
+        assert_eq!(model.apply(2.0), 1.0);
+        let (rmse, mae) = errors(&[0.0, 0.5], &[0.3, 0.1]);
+        assert!((rmse - 0.125_f32.sqrt()).abs() < 1e-6);
+        assert!((mae - 0.35).abs() < 1e-6);
+
+        let filename = test_directory("score_model").join("score_model.json");
+        let filename = filename.to_str().unwrap();
+        model.save(filename).unwrap();
+        let loaded = ScoreModel::load(filename).unwrap();
+        assert_eq!(
+            (loaded.training, loaded.held_out),
+            (model.training, model.held_out)
+        );
+        // models saved before the errors were split are still read
+        std::fs::write(
+            filename,
+            r#"{"coefficients": [0.0, 1.0], "rmse": 0.1, "mae": 0.1, "days": 2}"#,
+        )
+        .unwrap();
+        assert_eq!(ScoreModel::load(filename).unwrap().held_out, None);
+    }
+
+    #[test]
+    fn config_endpoint() {
+        let config: Config = serde_json::from_str(
+            r#"{"endpoints": {"cemantle": {"base_url": "http://localhost:8080/", "nearby_path": "near"}}}"#,
+        )
+        .unwrap();
+        let endpoint = config.endpoint(Game::Cemantle);
+        assert_eq!(endpoint.score_url(), "http://localhost:8080/score");
+        assert_eq!(endpoint.nearby_url(), "http://localhost:8080/near");
+        assert_eq!(endpoint.origin(), "http://localhost:8080");
+        assert_eq!(
+            config.endpoint(Game::Cemantix).score_url(),
+            "https://cemantix.certitudes.org/score"
+        );
+        assert_eq!(
+            serde_json::from_str::<Config>("{}").unwrap(),
+            Config::default()
+        );
+    }
+
+    #[test]
+    fn pedantix_article() {
+        let mut article = Article::default();
+        for (guess, body) in [
+            ("le", r#"{"score": {"4": "la"}}"#),
+            ("est", r#"{"score": {"2": "est"}}"#),
+            ("paris", r#"{"score": {"0": "Paris", "5": 0.4}}"#),
+            ("lyon", r#"{"score": {"1": 0.6, "5": 0.2}}"#),
+        ] {
+            article.update(&PedantixResponse::parse(guess, body).unwrap());
+        }
+        assert_eq!(article.revealed(), 3);
+        assert_eq!(
+            article.positions[&5],
+            Reveal::Close(String::from("paris"), 0.4)
+        );
+        assert_eq!(article.render(), "Paris [lyon] est █ la [paris]");
+        assert_eq!(article.title_candidates(), vec!["Paris lyon", "Paris"]);
+        // the title has a gap at the position 1
+        assert_eq!(article.title(), None);
+        article.update(&PedantixResponse::parse("lyon", r#"{"score": {"1": "Lyon"}}"#).unwrap());
+        assert_eq!(article.title().as_deref(), Some("Paris Lyon"));
+
+        assert!(
+            PedantixResponse::parse("x", r#"{"win": true}"#)
+                .unwrap()
+                .win
+        );
+        assert!(PedantixResponse::parse("x", r#"{"error": "unknown"}"#).is_err());
+    }
+
+    #[test]
+    fn puzzle_day() {
+        assert_eq!(puzzle_number(Game::Cemantix, date("02-03-2022")), 1);
+        assert_eq!(puzzle_number(Game::Cemantix, date("03-03-2022")), 2);
+        for game in [Game::Cemantix, Game::Cemantle, Game::Pedantix] {
+            assert_eq!(puzzle_number(game, game.epoch()), 1);
+        }
+        assert_ne!(
+            puzzle_number(Game::Cemantix, date("02-07-2024")),
+            puzzle_number(Game::Cemantle, date("02-07-2024"))
+        );
+
+        // 23:30 UTC is already the next day in Paris
+        let instant = chrono::DateTime::parse_from_rfc3339("2024-06-30T23:30:00Z").unwrap();
+        assert_eq!(puzzle_date_at(&instant), date("01-07-2024"));
+        let instant = chrono::DateTime::parse_from_rfc3339("2024-07-01T20:00:00-07:00").unwrap();
+        assert_eq!(puzzle_date_at(&instant), date("02-07-2024"));
+
+        let cemantix = Game::Cemantix;
+        let history =
+            WordGetter::parse_history("chat : 01-07-2024\nsel : 02-07-2024 : 854\n").unwrap();
+        assert_eq!(history[1], (String::from("sel"), date("02-07-2024")));
+        assert_eq!(
+            WordGetter::parse_history_line("sel : 02-07-2024 : 854").unwrap(),
+            (String::from("sel"), date("02-07-2024"), Some(854))
+        );
+        assert_eq!(
+            WordGetter::format_history_line("sel", &date("02-07-2024"), cemantix),
+            "sel : 02-07-2024 : 854\n"
+        );
+        assert!(WordGetter::parse_history("sel : 02-07-2024 : x").is_err());
+        // the date is trusted when the number does not match it
+        assert_eq!(
+            WordGetter::parse_history("sel : 02-07-2024 : 853").unwrap()[0].1,
+            date("02-07-2024")
+        );
+
+        // the separators inside a title are escaped
+        let line = WordGetter::format_history_line(
+            "Star Wars : Episode \\",
+            &date("02-07-2024"),
+            Game::Pedantix,
+        );
+        assert!(line.starts_with(r"Star Wars \: Episode \\ : 02-07-2024 : "));
+        assert_eq!(
+            WordGetter::parse_history(&line).unwrap()[0].0,
+            "Star Wars : Episode \\"
+        );
+    }
+
+    #[test]
+    fn check_invalid_file() {
+        let directory = std::env::temp_dir().join("cemantix_check");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("latin"), [0xff, 0xfe, b'[']).unwrap();
+        std::fs::write(directory.join("chat"), r#"[["chien",990,0.6]]"#).unwrap();
+        let directory = directory.display().to_string();
+
+        let issues = Check::check_file("latin", &directory).unwrap();
+        assert!(matches!(issues[..], [Issue::InvalidJson(_)]));
+        assert!(Check::check_file("chat", &directory).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn check_repair() {
+        let directory = test_directory("cemantix_check_repair");
+        write_found_word(&directory, "chat", &[("chien", 990, 0.6)]);
+        write_found_word(&directory, "orphelin", &[("seul", 990, 0.6)]);
+        std::fs::write(
+            directory.join("words_folder").join("chat"),
+            r#"[["souris",999,0.7],["chien",990,0.6]]"#,
+        )
+        .unwrap();
+        std::fs::write(directory.join("words_history"), "chat : 01-07-2024\n").unwrap();
+        // a directory of the user named like the repair directory of older versions is kept
+        std::fs::create_dir(directory.join(".repair")).unwrap();
+        let words_directory = directory.join("words_folder").display().to_string();
+        let issues = Check::check_file("chat", &words_directory).unwrap();
+        assert!(matches!(issues[..], [Issue::Unsorted]));
+
+        run(&directory, &["check", "--repair"]).await.unwrap();
+        assert!(Check::check_file("chat", &words_directory)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            WordGetter::get_cemantix_words_of_found_word("chat", &words_directory)
+                .unwrap()
+                .len(),
+            2
+        );
+        // the file of a word missing from the history is only removed on demand
+        assert!(directory.join("words_folder").join("orphelin").exists());
+        run(&directory, &["check", "--remove-orphans"])
+            .await
+            .unwrap();
+        assert!(!directory.join("words_folder").join("orphelin").exists());
+        assert!(directory.join(".repair").exists());
+        assert_eq!(
+            std::fs::read_dir(&directory)
+                .unwrap()
+                .filter(|e| e
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .starts_with(".cemantix_repair"))
+                .count(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn check_puzzle_numbers() {
+        let directory = test_directory("cemantix_check_puzzle_numbers");
+        write_found_word(&directory, "chat", &[("chien", 990, 0.6)]);
+        let history = directory.join("words_history");
+        std::fs::write(&history, "chat : 01-07-2024 : 1\n").unwrap();
+        // the history is still read, check rewrites the number of the date
+        run(&directory, &["check", "--repair"]).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&history).unwrap(),
+            WordGetter::format_history_line("chat", &date("01-07-2024"), Game::Cemantix)
+        );
+    }
+
+    fn date(s: &str) -> chrono::NaiveDate {
+        chrono::NaiveDate::parse_from_str(s, crate::HISTORY_FORMAT).unwrap()
+    }
+
+    /// returns an empty directory of the temporary directory
+    fn test_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// writes the file of a found word into the words directory of `working_directory`
+    fn write_found_word(working_directory: &PathBuf, word: &str, words: &[(&str, isize, f32)]) {
+        let directory = working_directory.join("words_folder");
+        std::fs::create_dir_all(&directory).unwrap();
+        let words = words
+            .iter()
+            .map(|(w, rank, score)| CemantixWord::new(w.to_string(), *rank, *score))
+            .collect::<Vec<CemantixWord>>();
+        WordGetter::save_cemantix_words_of_found_word(
+            word,
+            &words,
+            &directory.display().to_string(),
+        )
+        .unwrap();
+    }
+
+    async fn run(working_directory: &PathBuf, args: &[&str]) -> anyhow::Result<()> {
+        let working_directory = working_directory.display().to_string();
+        let mut cli = Cli::parse_from(
+            ["cemantix_ia", "--working-directory", &working_directory]
+                .iter()
+                .chain(args),
+        );
+        cli.matching().await
+    }
+
+    /// writes an archive whose manifest lists every file with the given checksum, or the right one
+    fn write_archive(path: &PathBuf, files: &[(&str, &[u8])], sha256: Option<&str>) {
+        let manifest = Manifest {
+            version: 1,
+            created: String::new(),
+            files: files
+                .iter()
+                .map(|(name, data)| ManifestEntry {
+                    path: name.to_string(),
+                    size: data.len() as u64,
+                    sha256: sha256.map_or_else(|| Export::sha256(data), str::to_owned),
+                })
+                .collect(),
+        };
+        let manifest = serde_json::to_vec(&manifest).unwrap();
+        let mut builder = tar::Builder::new(std::fs::File::create(path).unwrap());
+        for (name, data) in [(MANIFEST_NAME, &manifest[..])].iter().chain(files) {
+            // the name is written as is, tar refuses to build paths containing ..
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, *data).unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    #[tokio::test]
+    async fn export_import() {
+        let source = test_directory("cemantix_export");
+        std::fs::write(source.join("words_history"), "chat : 01-07-2024\n").unwrap();
+        std::fs::create_dir_all(source.join("words_folder")).unwrap();
+        let chat = r#"[["chien",990,0.6]]"#;
+        std::fs::write(source.join("words_folder/chat"), chat).unwrap();
+        let archive = source.join("data.tar.gz").display().to_string();
+        run(&source, &["export", "--compress", &archive])
+            .await
+            .unwrap();
+
+        let destination = test_directory("cemantix_import");
+        run(&destination, &["import", &archive]).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(destination.join("words_history")).unwrap(),
+            "chat : 01-07-2024\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(destination.join("words_folder/chat")).unwrap(),
+            chat
+        );
+
+        let corrupted = source.join("corrupted.tar");
+        write_archive(
+            &corrupted,
+            &[("words_folder/chat", chat.as_bytes())],
+            Some("0"),
+        );
+        let corrupted = corrupted.display().to_string();
+        assert!(Import::read_archive(&corrupted).is_err());
+        // nothing is written from an archive whose files do not match the manifest
+        let destination = test_directory("cemantix_import_corrupted");
+        assert!(run(&destination, &["import", &corrupted]).await.is_err());
+        assert!(!destination.join("words_folder").exists());
+
+        let malicious = source.join("malicious.tar");
+        write_archive(
+            &malicious,
+            &[
+                ("words_folder/../escaped", chat.as_bytes()),
+                ("sessions/../../escaped", chat.as_bytes()),
+            ],
+            None,
+        );
+        let destination = test_directory("cemantix_import_malicious");
+        let malicious = malicious.display().to_string();
+        run(&destination, &["import", &malicious]).await.unwrap();
+        assert!(!destination.join("escaped").exists());
+        assert!(!std::env::temp_dir().join("escaped").exists());
+        assert!(!destination.join("words_folder").exists());
+    }
+
+    #[tokio::test]
+    async fn merge_working_directories() {
+        let local = test_directory("cemantix_merge_local");
+        let other = test_directory("cemantix_merge_other");
+        std::fs::write(local.join("words_history"), "chat : 01-07-2024\n").unwrap();
+        std::fs::write(
+            other.join("words_history"),
+            "chien : 01-07-2024\nsel : 02-07-2024\n",
+        )
+        .unwrap();
+        for directory in [&local, &other] {
+            std::fs::create_dir_all(directory.join("words_folder")).unwrap();
+        }
+        std::fs::write(local.join("words_folder/chat"), r#"[["chien",990,0.6]]"#).unwrap();
+        std::fs::write(
+            other.join("words_folder/chat"),
+            r#"[["loup",0,0.3],["chien",995,0.61]]"#,
+        )
+        .unwrap();
+
+        let other = other.display().to_string();
+        run(&local, &["merge", &other]).await.unwrap();
+        let history =
+            WordGetter::get_history(&local.join("words_history").display().to_string()).unwrap();
+        // the local word is kept on conflicts
+        assert_eq!(
+            history,
+            vec![
+                (String::from("chat"), date("01-07-2024")),
+                (String::from("sel"), date("02-07-2024"))
+            ]
+        );
+        let words = WordGetter::get_cemantix_words_of_found_word(
+            "chat",
+            &local.join("words_folder").display().to_string(),
+        )
+        .unwrap();
+        assert_eq!(words.len(), 2);
+        assert!(words.iter().any(|w| w.word == "chien" && w.rank == 995));
+    }
+
+    #[tokio::test]
+    async fn semantic_graph_export() {
+        let directory = test_directory("cemantix_graph");
+        std::fs::create_dir_all(directory.join("words_folder")).unwrap();
+        std::fs::write(
+            directory.join("words_folder/a\"b"),
+            r#"[["<z>",0,0.2],["x&y",990,0.6]]"#,
+        )
+        .unwrap();
+        // an empty file is ignored instead of failing the export
+        std::fs::write(directory.join("words_folder/vide"), "").unwrap();
+        let output = directory.join("graph.json").display().to_string();
+        run(
+            &directory,
+            &["graph", "export", "-f", "json", "-o", &output],
+        )
+        .await
+        .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        let words = json["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n["word"].as_str().unwrap())
+            .collect::<Vec<&str>>();
+        assert_eq!(words, vec!["a\"b", "x&y", "<z>"]);
+        assert_eq!(json["edges"].as_array().unwrap().len(), 2);
+
+        let mut graph = SemanticGraph::new();
+        graph.add_edge("a\"b", "x&y", 0.6, 990);
+        graph.add_edge("a\"b", "<z>", 0.2, 0);
+        let dot = graph.to_dot();
+        assert!(dot.contains(r#"n0 [label="a\"b"];"#));
+        assert!(dot.contains(r#"n2 [label="<z>"];"#));
+        assert!(dot.contains(r#"n0 -> n1 [weight=60, label="990"];"#));
+        let graphml = graph.to_graphml();
+        assert!(graphml.contains(r#"<data key="word">a&quot;b</data>"#));
+        assert!(graphml.contains(r#"<data key="word">x&amp;y</data>"#));
+        assert!(graphml.contains(r#"<data key="word">&lt;z&gt;</data>"#));
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json().unwrap()).unwrap();
+        assert_eq!(json["nodes"][2]["word"], "<z>");
+    }
+
+    #[tokio::test]
+    async fn clusters() {
+        let directory = test_directory("cemantix_clusters");
+        let animals = [("patte", 0, 0.3), ("poil", 980, 0.5), ("queue", 990, 0.6)];
+        let sea = [("sel", 0, 0.3), ("vague", 980, 0.5), ("plage", 990, 0.6)];
+        write_found_word(&directory, "chat", &animals);
+        write_found_word(&directory, "chien", &animals[1..]);
+        write_found_word(&directory, "mer", &sea);
+        write_found_word(&directory, "ocean", &sea[..2]);
+        std::fs::write(directory.join("words_folder/vide"), "").unwrap();
+
+        let output = directory.join("clusters.json").display().to_string();
+        run(&directory, &["clusters", "-l", "1", "--json", &output])
+            .await
+            .unwrap();
+        let clusters: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        let mut members = clusters
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| {
+                let mut members = c["members"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|m| m.as_str().unwrap())
+                    .collect::<Vec<&str>>();
+                members.sort();
+                members
+            })
+            .collect::<Vec<Vec<&str>>>();
+        members.sort();
+        assert_eq!(members, vec![vec!["chat", "chien"], vec!["mer", "ocean"]]);
+    }
+
+    #[tokio::test]
+    async fn map_layout() {
+        let scores = [0.8, 0.3, 0.3];
+        let similarities = vec![
+            vec![0.0, 0.0, 0.0],
+            vec![0.0, 0.0, 0.9],
+            vec![0.0, 0.9, 0.0],
+        ];
+        let positions = Map::layout(&scores, &similarities);
+        let distance = |a: usize, b: usize| {
+            let (dx, dy) = (
+                positions[a].0 - positions[b].0,
+                positions[a].1 - positions[b].1,
+            );
+            (dx * dx + dy * dy).sqrt()
+        };
+        assert_eq!(positions[0], (0.0, 0.0));
+        // the closest word is the nearest to the center, similar words are drawn together
+        assert!(distance(0, 1) < distance(0, 2));
+        assert!(distance(2, 3) < distance(1, 2));
+
+        let words = ["chat", "chien", "a<b", "x&y"].map(String::from);
+        let svg = Map::render_svg(&words, &positions, &[0, 1, 1]);
+        assert_eq!(svg.matches("<circle").count(), 4);
+        assert!(svg.contains(">a&lt;b</text>") && svg.contains(">x&amp;y</text>"));
+
+        let directory = test_directory("cemantix_map");
+        write_found_word(
+            &directory,
+            "chat",
+            &[("souris", 0, 0.3), ("chien", 990, 0.6)],
+        );
+        let output = directory.join("chat.svg").display().to_string();
+        run(&directory, &["map", "chat", "-o", &output])
+            .await
+            .unwrap();
+        let svg = std::fs::read_to_string(&output).unwrap();
+        assert_eq!(svg.matches("<circle").count(), 3);
+    }
+
+    #[tokio::test]
+    async fn report() {
+        let directory = test_directory("cemantix_report");
+        std::fs::write(
+            directory.join("words_history"),
+            "chat : 01-07-2024\nsel : 02-07-2024\n",
+        )
+        .unwrap();
+        write_found_word(&directory, "chat", &[("a<b", 0, 0.3), ("chien", 990, 0.6)]);
+        let session = Session {
+            word: String::from("chat"),
+            date: String::from("01-07-2024"),
+            duration_ms: 1500,
+            requests: 42,
+            timeline: vec![Guess {
+                elapsed_ms: 200,
+                word: String::from("chien"),
+                score: 0.6,
+            }],
+        };
+        let sessions = directory.join("sessions").display().to_string();
+        session.save(&sessions).unwrap();
+        assert_eq!(Session::load("chat", &sessions).unwrap().requests, 42);
+
+        let output = directory.join("report");
+        let output_name = output.display().to_string();
+        run(&directory, &["report", "-o", &output_name])
+            .await
+            .unwrap();
+        let page = std::fs::read_to_string(output.join("chat.html")).unwrap();
+        assert!(page.contains("<p>42 requests in 1.5s</p>"));
+        assert!(page.contains("<tr><td>chien</td><td>0.6000</td><td>0.2s</td></tr>"));
+        assert!(page.contains("<td>a&lt;b</td>"));
+        // the word without file has no page
+        let index = std::fs::read_to_string(output.join("index.html")).unwrap();
+        assert!(index.contains("href=\"chat.html\""));
+        assert!(!index.contains("sel.html") && !output.join("sel.html").exists());
+        assert_eq!(href("bébé #1"), "b%C3%A9b%C3%A9%20%231.html");
+    }
+
+    #[test]
+    fn analyze_distribution() {
+        let words = [
+            ("chat", 1000, 1.0),
+            ("chien", 999, 0.6),
+            ("souris", 990, 0.45),
+            ("mer", 0, 0.2),
+            ("sel", 0, -0.1),
+        ]
+        .map(|(w, rank, score)| CemantixWord::new(w.to_string(), rank, score));
+        let d = Distribution::new("chat", &words);
+        // the found word itself is left out
+        assert_eq!(d.scores, vec![-0.1, 0.2, 0.45, 0.6]);
+        assert!((d.mean - 0.2875).abs() < 1e-6);
+        // lowest score with a known rank
+        assert_eq!(d.top_score, 0.45);
+        assert_eq!(d.count_above(0.45), 2);
+        assert_eq!(d.count_above(0.5), 1);
+
+        // without ranks, the 1000th best score or the lowest one
+        let d = Distribution::new("chat", &words[3..]);
+        assert_eq!(d.top_score, -0.1);
+    }
+
+    #[test]
+    fn diff_neighbourhoods() {
+        let words = |list: &[(&str, f32)]| {
+            list.iter()
+                .map(|(w, score)| CemantixWord::new(w.to_string(), 0, *score))
+                .collect::<Vec<CemantixWord>>()
+        };
+        let a = Diff::neighbourhood(
+            "chat",
+            words(&[
+                ("souris", 0.4),
+                ("chat", 1.0),
+                ("chien", 0.6),
+                ("loup", 0.3),
+            ]),
+            2,
+        );
+        // the found word is left out and only the closest words are kept
+        assert_eq!(
+            a.iter()
+                .map(|(p, w)| (*p, w.word.as_str()))
+                .collect::<Vec<(usize, &str)>>(),
+            vec![(1, "chien"), (2, "souris")]
+        );
+        let b = Diff::neighbourhood(
+            "chien",
+            words(&[("souris", 0.5), ("loup", 0.7), ("chat", 0.6)]),
+            3,
+        );
+        let shared = Diff::shared(&a, &b);
+        assert_eq!(shared.len(), 1);
+        assert_eq!((shared[0].0 .0, shared[0].1 .0), (2, 3));
+        assert_eq!(shared[0].1 .1.word, "souris");
+
+        // the words can be given by the date they were found
+        let directory = test_directory("cemantix_diff_days");
+        let history = directory.join("words_history").display().to_string();
+        std::fs::write(&history, "chat : 01-07-2024\n").unwrap();
+        assert_eq!(
+            WordGetter::resolve_day("01-07-2024", &history).unwrap(),
+            "chat"
+        );
+        assert_eq!(WordGetter::resolve_day("chien", &history).unwrap(), "chien");
+        assert!(WordGetter::resolve_day("02-07-2024", &history).is_err());
+    }
+
+    #[test]
+    fn offline_puzzle() {
+        let directory = test_directory("cemantix_offline_puzzle");
+        write_found_word(
+            &directory,
+            "chat",
+            &[
+                ("chien", 999, 0.7),
+                ("souris", 990, 0.5),
+                ("bug", 1200, 0.4),
+            ],
+        );
+        let words_directory = directory.join("words_folder").display().to_string();
+        let puzzle = Puzzle::from_found_word("chat", &words_directory, None).unwrap();
+        assert_eq!(puzzle.score("chat").unwrap().rank, MAX_RANK);
+        assert_eq!(puzzle.score("souris").unwrap().rank, 990);
+        // invalid ranks are dropped and no embeddings score the other words
+        assert!(puzzle.score("bug").is_none());
+        assert!(puzzle.score("maison").is_none());
+        let closest = puzzle.closest();
+        assert_eq!(
+            closest
+                .iter()
+                .map(|w| w.word.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["chat", "chien", "souris"]
+        );
+
+        let mut embeddings = Embeddings::new(2);
+        embeddings.push("chat", &[1.0, 0.0]);
+        embeddings.push("maison", &[0.0, 1.0]);
+        embeddings.push("chien", &[0.9, 0.1]);
+        let filename = directory.join("embeddings.bin").display().to_string();
+        EmbeddingsCache::write(&embeddings, &filename, false).unwrap();
+        let scorer = OfflineScorer::new(EmbeddingsCache::open(&filename).unwrap(), None);
+        let puzzle = Puzzle::from_embeddings("chat", scorer).unwrap();
+        assert_eq!(puzzle.score("chat").unwrap().score, 1.0);
+        let (chien, maison) = (
+            puzzle.score("chien").unwrap(),
+            puzzle.score("maison").unwrap(),
+        );
+        assert_eq!((chien.rank, maison.rank), (MAX_RANK - 1, MAX_RANK - 2));
+        assert!(chien.score > maison.score);
+        assert!(maison.score.abs() < 1e-6);
+        assert!(puzzle.score("souris").is_none());
+        let scorer = OfflineScorer::new(EmbeddingsCache::open(&filename).unwrap(), None);
+        assert!(Puzzle::from_embeddings("souris", scorer).is_err());
+    }
+
+    #[tokio::test]
+    async fn serve_requests() {
+        let directory = test_directory("cemantix_serve_requests");
+        write_found_word(
+            &directory,
+            "chat",
+            &[("souris", 990, 0.5), ("chien", 999, 0.7)],
+        );
+        let words_directory = directory.join("words_folder").display().to_string();
+        let puzzle = Puzzle::from_found_word("chat", &words_directory, None).unwrap();
+
+        let request = "POST /score HTTP/1.1\r\nContent-Length: 11\r\n\r\nword=Chien+";
+        let request = Serve::read_request(request.as_bytes()).await.unwrap();
+        assert_eq!(
+            (request.method.as_str(), request.path.as_str()),
+            ("POST", "/score")
+        );
+        let (status, body) = Serve::respond(&request, &puzzle, &words_directory);
+        assert_eq!(status, 200);
+        assert!(body.contains(r#""percentile":999"#));
+
+        let request = Serve::read_request("GET /nearby?word=chat HTTP/1.1\r\n\r\n".as_bytes())
+            .await
+            .unwrap();
+        let (status, body) = Serve::respond(&request, &puzzle, &words_directory);
+        assert_eq!(status, 200);
+        assert!(body.find("souris").unwrap() < body.find("chien").unwrap());
+        let request = Serve::read_request("GET /nearby?word=chien HTTP/1.1\r\n\r\n".as_bytes())
+            .await
+            .unwrap();
+        assert!(Serve::respond(&request, &puzzle, &words_directory)
+            .1
+            .contains("error"));
+        // the words are not paths, even to a file of the words directory
+        std::fs::copy(
+            directory.join("words_folder").join("chat"),
+            directory.join("outside"),
+        )
+        .unwrap();
+        for word in [
+            "../outside",
+            "..%2Foutside",
+            "..\\outside",
+            "%2Ftmp%2Foutside",
+        ] {
+            let request = format!("GET /nearby?word={word} HTTP/1.1\r\n\r\n");
+            let request = Serve::read_request(request.as_bytes()).await.unwrap();
+            let (status, body) = Serve::respond(&request, &puzzle, &words_directory);
+            assert_eq!(status, 200);
+            assert!(body.contains("error"), "{word} : {body}");
+        }
+
+        // the body of a request above the cap is not read
+        let request = format!(
+            "POST /score HTTP/1.1\r\nContent-Length: {}\r\n\r\nword=chien",
+            MAX_BODY_SIZE + 1
+        );
+        let request = Serve::read_request(request.as_bytes()).await.unwrap();
+        assert!(request.form.is_empty());
+        assert_eq!(Serve::respond(&request, &puzzle, &words_directory).0, 413);
+
+        let request = Serve::read_request("DELETE /score HTTP/1.1\r\n\r\n".as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(Serve::respond(&request, &puzzle, &words_directory).0, 405);
+        let request = Serve::read_request("GET /other HTTP/1.1\r\n\r\n".as_bytes())
+            .await
+            .unwrap();
+        assert_eq!(Serve::respond(&request, &puzzle, &words_directory).0, 404);
+        assert!(Serve::read_request("\r\n".as_bytes()).await.is_err());
+        let request = "POST /score HTTP/1.1\r\nContent-Length: many\r\n\r\n";
+        assert!(Serve::read_request(request.as_bytes()).await.is_err());
+        let request = format!(
+            "GET /score?word={} HTTP/1.1\r\n\r\n",
+            "a".repeat(MAX_LINE_SIZE)
+        );
+        assert!(Serve::read_request(request.as_bytes()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn game_files() {
+        let directory = test_directory("cemantix_game_files");
+        write_found_word(&directory, "chat", &[("chien", 990, 0.6)]);
+        // an explicit file is kept, the other ones are the files of the game
+        run(
+            &directory,
+            &[
+                "--game",
+                "cemantle",
+                "--words-directory",
+                "words_folder/",
+                "lookup",
+                "chien",
+            ],
+        )
+        .await
+        .unwrap();
+        let index = std::fs::read_to_string(directory.join("cemantle_index")).unwrap();
+        assert!(index.contains("chat"));
+        assert!(!directory.join("words_index").exists());
+
+        let error = run(&directory, &["--game", "cemantle", "solve"])
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("source file"));
+    }
 }
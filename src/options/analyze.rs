@@ -0,0 +1,136 @@
+use anyhow::Result;
+use clap::Args;
+
+use crate::{
+    cemantix_word::{CemantixWord, MAX_RANK},
+    stats::{histogram, mean, percentile},
+    words_getter::WordGetter,
+};
+
+use super::options::{Cli, LogLevel};
+
+const HISTOGRAM_BINS: usize = 20;
+const HISTOGRAM_WIDTH: usize = 60;
+const PERCENTILES: [f32; 7] = [1.0, 10.0, 25.0, 50.0, 75.0, 90.0, 99.0];
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Analyze {
+    /// Found word whose scores are analysed
+    #[arg(required_unless_present = "all")]
+    pub word: Option<String>,
+
+    /// Compare the distributions of the scores of every found word
+    #[arg(short, long, default_value_t = false, conflicts_with = "word")]
+    pub all: bool,
+}
+
+/// summary of the scores of the closest words of a found word
+#[derive(Debug)]
+pub struct Distribution {
+    /// scores sorted in ascending order
+    pub scores: Vec<f32>,
+    pub mean: f32,
+    /// score of the farthest word of the top 1000 of the game
+    pub top_score: f32,
+}
+
+impl Distribution {
+    pub fn new(word: &str, words: &[CemantixWord]) -> Self {
+        let mut scores = words
+            .iter()
+            .filter(|w| w.word != word)
+            .map(|w| w.score)
+            .collect::<Vec<f32>>();
+        scores.sort_by(f32::total_cmp);
+        // ranks are only known for the top 1000, otherwise take the 1000th best score
+        let top_score = words
+            .iter()
+            .filter(|w| w.rank > 0 && w.word != word)
+            .map(|w| w.score)
+            .reduce(f32::min)
+            .unwrap_or_else(|| {
+                scores
+                    .len()
+                    .checked_sub(MAX_RANK as usize)
+                    .map_or(scores.first().copied().unwrap_or_default(), |i| scores[i])
+            });
+        Self {
+            mean: mean(&scores),
+            scores,
+            top_score,
+        }
+    }
+
+    pub fn count_above(&self, threshold: f32) -> usize {
+        self.scores.len() - self.scores.partition_point(|s| *s < threshold)
+    }
+}
+
+impl Analyze {
+    fn print_distribution(word: &str, d: &Distribution) {
+        println!(
+            "{word} : {} words, mean score {:.4}",
+            d.scores.len(),
+            d.mean
+        );
+        for p in PERCENTILES.iter() {
+            println!("  p{:<3} {:>8.4}", p, percentile(&d.scores, *p));
+        }
+        for (name, threshold) in [("1000th word", d.top_score), ("0.5", 0.5), ("0.3", 0.3)] {
+            println!(
+                "  above {name} ({threshold:.4}) : {}",
+                d.count_above(threshold)
+            );
+        }
+
+        let min = d.scores.first().copied().unwrap_or_default().min(0.0);
+        let counts = histogram(&d.scores, HISTOGRAM_BINS, min, 1.0);
+        let highest = counts.iter().copied().max().unwrap_or(1).max(1);
+        let width = (1.0 - min) / HISTOGRAM_BINS as f32;
+        for (i, count) in counts.iter().enumerate() {
+            println!(
+                "  {:>6.3} | {:<HISTOGRAM_WIDTH$} {count}",
+                min + i as f32 * width,
+                "#".repeat(count * HISTOGRAM_WIDTH / highest)
+            );
+        }
+    }
+
+    pub async fn analyze(&self, cli: &Cli) -> Result<()> {
+        if let Some(word) = self.word.as_ref() {
+            let words = WordGetter::get_cemantix_words_of_found_word(word, &cli.words_directory)?;
+            Self::print_distribution(word, &Distribution::new(word, &words));
+            return Ok(());
+        }
+
+        let mut distributions = Vec::new();
+        for word in WordGetter::get_all_found_word(&cli.words_directory)? {
+            match WordGetter::get_cemantix_words_of_found_word(&word, &cli.words_directory) {
+                Ok(words) => {
+                    let d = Distribution::new(&word, &words);
+                    distributions.push((word, d));
+                }
+                Err(e) => cli.log_and_print(&format!("Ignoring {word} : {e}"), LogLevel::Warn)?,
+            }
+        }
+        // the lower the score of the 1000th word, the less the game helps near the answer
+        distributions.sort_by(|a, b| a.1.top_score.total_cmp(&b.1.top_score));
+        println!(
+            "{:<20} {:>7} {:>8} {:>8} {:>8} {:>7} {:>7}",
+            "word", "words", "mean", "p99", "1000th", ">0.5", ">0.3"
+        );
+        for (word, d) in distributions.iter() {
+            println!(
+                "{:<20} {:>7} {:>8.4} {:>8.4} {:>8.4} {:>7} {:>7}",
+                word,
+                d.scores.len(),
+                d.mean,
+                percentile(&d.scores, 99.0),
+                d.top_score,
+                d.count_above(0.5),
+                d.count_above(0.3)
+            );
+        }
+        Ok(())
+    }
+}
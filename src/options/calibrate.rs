@@ -0,0 +1,144 @@
+use anyhow::Result;
+use clap::Args;
+
+use crate::{
+    embeddings::{
+        cache::EmbeddingsCache,
+        scorer::{FitErrors, ScoreModel},
+    },
+    stats::{errors, polyfit},
+    words_getter::WordGetter,
+};
+
+use super::options::{Cli, LogLevel};
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Calibrate {
+    /// Degree of the polynomial mapping the similarity of the embeddings to the score
+    #[arg(short, long, default_value_t = 3)]
+    pub degree: usize,
+
+    /// One found word out of this number is kept out of the fit to evaluate the model, 0 for none
+    #[arg(long, default_value_t = 5)]
+    pub holdout: usize,
+}
+
+/// similarities of the embeddings and scores of the game
+#[derive(Debug, Default)]
+struct Samples {
+    similarities: Vec<f32>,
+    scores: Vec<f32>,
+    days: usize,
+}
+
+impl Samples {
+    fn errors(&self, predict: impl Fn(f32) -> f32) -> (f32, f32) {
+        let predictions = self
+            .similarities
+            .iter()
+            .map(|s| predict(*s))
+            .collect::<Vec<f32>>();
+        errors(&predictions, &self.scores)
+    }
+}
+
+impl Calibrate {
+    pub async fn calibrate(&self, cli: &Cli) -> Result<()> {
+        let cache = EmbeddingsCache::open(&cli.embeddings)?;
+        let mut found_words = WordGetter::get_all_found_word(&cli.words_directory)?;
+        found_words.sort();
+
+        let (mut train, mut test) = (Samples::default(), Samples::default());
+        let mut skipped = 0;
+        for found_word in found_words.iter() {
+            let Some(answer) = cache.index(found_word) else {
+                skipped += 1;
+                continue;
+            };
+            let words = match WordGetter::get_cemantix_words_of_found_word(
+                found_word,
+                &cli.words_directory,
+            ) {
+                Ok(w) => w,
+                Err(e) => {
+                    cli.log_and_print(&format!("Ignoring {found_word} : {e}"), LogLevel::Warn)?;
+                    continue;
+                }
+            };
+            let vector = cache.vector(answer);
+            let samples = if self.holdout > 0 && (train.days + test.days) % self.holdout == 0 {
+                &mut test
+            } else {
+                &mut train
+            };
+            samples.days += 1;
+            for w in words
+                .iter()
+                .filter(|w| w.is_valid() && &w.word != found_word)
+            {
+                if let Some(i) = cache.index(&w.word) {
+                    samples.similarities.push(cache.similarity_to(&vector, i));
+                    samples.scores.push(w.score);
+                }
+            }
+        }
+        if skipped > 0 {
+            cli.log_and_print(
+                &format!("{skipped} found words are not in the embeddings"),
+                LogLevel::Warn,
+            )?;
+        }
+
+        let Some(coefficients) = polyfit(&train.similarities, &train.scores, self.degree) else {
+            return Err(anyhow::anyhow!(
+                "Not enough scores to fit a polynomial of degree {}",
+                self.degree
+            ));
+        };
+        let mut model = ScoreModel {
+            coefficients,
+            training: FitErrors::default(),
+            held_out: None,
+            days: train.days,
+        };
+
+        println!(
+            "{:<10} {:>6} {:>8} {:>10} {:>10} {:>10} {:>10}",
+            "set", "days", "scores", "raw rmse", "raw mae", "rmse", "mae"
+        );
+        for (name, samples) in [("training", &train), ("held-out", &test)] {
+            if samples.days == 0 {
+                continue;
+            }
+            let (raw_rmse, raw_mae) = samples.errors(|s| s);
+            let (rmse, mae) = samples.errors(|s| model.apply(s));
+            println!(
+                "{name:<10} {:>6} {:>8} {raw_rmse:>10.4} {raw_mae:>10.4} {rmse:>10.4} {mae:>10.4}",
+                samples.days,
+                samples.scores.len()
+            );
+        }
+        let fit_errors = |samples: &Samples| {
+            let (rmse, mae) = samples.errors(|s| model.apply(s));
+            FitErrors { rmse, mae }
+        };
+        let (training, held_out) = (
+            fit_errors(&train),
+            (test.days > 0).then(|| fit_errors(&test)),
+        );
+        (model.training, model.held_out) = (training, held_out);
+        model.save(&cli.score_model)?;
+        let held_out = match model.held_out {
+            Some(e) => format!("held-out rmse {:.4}, mae {:.4}", e.rmse, e.mae),
+            None => "no held-out words".to_string(),
+        };
+        cli.log_and_print(
+            &format!(
+                "Model learnt from {} found words written into {} (training rmse {:.4}, mae {:.4}, {held_out})",
+                model.days, cli.score_model, model.training.rmse, model.training.mae
+            ),
+            LogLevel::Info,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    fs,
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::{cemantix_word::CemantixWord, day::puzzle_number, words_getter::WordGetter};
+
+use super::{
+    nearby::Nearby,
+    options::{Cli, LogLevel},
+};
+
+/// prefix of the directory of the working directory in which the repaired files are written
+/// before replacing, the directory is named after the process to never be an existing one
+const REPAIR_DIRECTORY_PREFIX: &str = ".cemantix_repair";
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Check {
+    /// Refetch or rebuild the broken entries
+    #[arg(short, long, default_value_t = false)]
+    pub repair: bool,
+
+    /// Remove the files of the words missing from the history
+    #[arg(long, default_value_t = false)]
+    pub remove_orphans: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Issue {
+    /// the file of the found word is empty
+    Empty,
+    /// the file of the found word is not a list of words
+    InvalidJson(String),
+    /// some words are stored several times
+    Duplicates(usize),
+    /// some words have a score or a rank out of range
+    OutOfRange(usize),
+    /// the word is in the history but has no file
+    MissingFile,
+    /// the word has a file but is not in the history
+    NotInHistory,
+    /// the words of the file are not sorted
+    Unsorted,
+    /// the puzzle number stored in the history is not the one of the date
+    PuzzleNumber(i64, i64),
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Issue::Empty => write!(f, "empty file"),
+            Issue::InvalidJson(e) => write!(f, "invalid content ({e})"),
+            Issue::Duplicates(n) => write!(f, "{n} duplicated words"),
+            Issue::OutOfRange(n) => write!(f, "{n} words with a score or a rank out of range"),
+            Issue::MissingFile => write!(f, "in the history but no file found"),
+            Issue::NotInHistory => write!(f, "file found but not in the history"),
+            Issue::Unsorted => write!(f, "words not sorted"),
+            Issue::PuzzleNumber(number, expected) => write!(
+                f,
+                "puzzle number {number} in the history instead of {expected} for its date"
+            ),
+        }
+    }
+}
+
+impl Check {
+    pub fn new(repair: bool, remove_orphans: bool) -> Self {
+        Self {
+            repair,
+            remove_orphans,
+        }
+    }
+
+    /// returns all the issues of the file of a found word
+    pub fn check_file(word: &str, words_fcontainer_name: &str) -> Result<Vec<Issue>> {
+        let content =
+            match String::from_utf8(fs::read(PathBuf::from(words_fcontainer_name).join(word))?) {
+                Ok(c) => c,
+                Err(e) => return Ok(vec![Issue::InvalidJson(e.to_string())]),
+            };
+        if content.trim().is_empty() {
+            return Ok(vec![Issue::Empty]);
+        }
+        let words = match serde_json::from_str::<Vec<CemantixWord>>(&content) {
+            Ok(w) => w,
+            Err(e) => return Ok(vec![Issue::InvalidJson(e.to_string())]),
+        };
+
+        let mut issues = Vec::new();
+        let unique = words.iter().map(|w| &w.word).collect::<HashSet<&String>>();
+        if unique.len() != words.len() {
+            issues.push(Issue::Duplicates(words.len() - unique.len()));
+        }
+        let out_of_range = words.iter().filter(|w| !w.is_valid()).count();
+        if out_of_range != 0 {
+            issues.push(Issue::OutOfRange(out_of_range));
+        }
+        if words.windows(2).any(|pair| pair[0] > pair[1]) {
+            issues.push(Issue::Unsorted);
+        }
+        Ok(issues)
+    }
+
+    /// gathers the issues of the words directory and of the history, sorted by word
+    pub fn collect_issues(cli: &Cli) -> Result<BTreeMap<String, Vec<Issue>>> {
+        let mut issues: BTreeMap<String, Vec<Issue>> = BTreeMap::new();
+        let found_words = WordGetter::get_all_found_word(&cli.words_directory).unwrap_or_default();
+        let history = match WordGetter::get_history(&cli.word_history) {
+            Ok(h) => h,
+            Err(e) => {
+                cli.log_and_print(
+                    &format!("Cannot read history {} : {e}", cli.word_history),
+                    LogLevel::Warn,
+                )?;
+                Vec::new()
+            }
+        };
+
+        for word in found_words.iter() {
+            let file_issues = Self::check_file(word, &cli.words_directory)?;
+            if !file_issues.is_empty() {
+                issues
+                    .entry(word.to_owned())
+                    .or_default()
+                    .extend(file_issues);
+            }
+            if !history.iter().any(|(w, _)| w == word) {
+                issues
+                    .entry(word.to_owned())
+                    .or_default()
+                    .push(Issue::NotInHistory);
+            }
+        }
+        for (word, _) in history.iter() {
+            if !found_words.contains(word) {
+                issues
+                    .entry(word.to_owned())
+                    .or_default()
+                    .push(Issue::MissingFile);
+            }
+        }
+        // the dates are trusted when reading the history, the numbers are only checked here
+        for line in fs::read_to_string(&cli.word_history)
+            .unwrap_or_default()
+            .lines()
+        {
+            if let Ok((word, date, Some(number))) = WordGetter::parse_history_line(line) {
+                let expected = puzzle_number(cli.game, date);
+                if number != expected {
+                    issues
+                        .entry(word)
+                        .or_default()
+                        .push(Issue::PuzzleNumber(number, expected));
+                }
+            }
+        }
+        Ok(issues)
+    }
+
+    pub async fn check_data(&self, cli: &Cli) -> Result<()> {
+        let issues = Self::collect_issues(cli)?;
+        for (word, word_issues) in issues.iter() {
+            for issue in word_issues.iter() {
+                cli.log_and_print(&format!("{word} : {issue}"), LogLevel::Warn)?;
+            }
+        }
+        cli.log_and_print(
+            &format!(
+                "{} issues found on {} words",
+                issues.values().map(|v| v.len()).sum::<usize>(),
+                issues.len()
+            ),
+            LogLevel::Info,
+        )?;
+
+        if self.remove_orphans {
+            for (word, word_issues) in issues.iter() {
+                if word_issues.iter().any(|i| matches!(i, Issue::NotInHistory)) {
+                    fs::remove_file(PathBuf::from(&cli.words_directory).join(word))?;
+                    cli.log_and_print(&format!("{word} : file removed"), LogLevel::Info)?;
+                }
+            }
+        }
+        if self.repair {
+            if issues
+                .values()
+                .flatten()
+                .any(|i| matches!(i, Issue::PuzzleNumber(..)))
+            {
+                let history = WordGetter::get_history(&cli.word_history)?;
+                WordGetter::save_history(&cli.word_history, &history, cli.game)?;
+                cli.log_and_print(
+                    "History rewritten with the puzzle numbers of its dates",
+                    LogLevel::Info,
+                )?;
+            }
+            for (word, word_issues) in issues.iter() {
+                let removed = self.remove_orphans
+                    && word_issues.iter().any(|i| matches!(i, Issue::NotInHistory));
+                let word_issues = word_issues
+                    .iter()
+                    .filter(|i| !matches!(i, Issue::PuzzleNumber(..)))
+                    .cloned()
+                    .collect::<Vec<Issue>>();
+                if removed || word_issues.is_empty() {
+                    continue;
+                }
+                if let Err(e) = self.repair_word(word, &word_issues, cli).await {
+                    cli.log_and_print(&format!("Cannot repair {word} : {e}"), LogLevel::Error)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// replaces the file of a word by the one written into `repair_directory` if it has no issue
+    fn replace_file(word: &str, repair_directory: &str, cli: &Cli) -> Result<()> {
+        let issues = Self::check_file(word, repair_directory)?;
+        if let Some(issue) = issues.first() {
+            return Err(anyhow::anyhow!("the new file is not valid : {issue}"));
+        }
+        fs::rename(
+            PathBuf::from(repair_directory).join(word),
+            PathBuf::from(&cli.words_directory).join(word),
+        )?;
+        Ok(())
+    }
+
+    async fn repair_word(&self, word: &str, issues: &[Issue], cli: &Cli) -> Result<()> {
+        // the new file is written aside, the broken one is kept until it is valid
+        // the directory is created here so that only a directory of this run is removed
+        let repair_directory = PathBuf::from(&cli.working_directory)
+            .join(format!("{REPAIR_DIRECTORY_PREFIX}_{}", std::process::id()))
+            .display()
+            .to_string();
+        fs::create_dir(&repair_directory).map_err(|e| {
+            anyhow::anyhow!("Cannot create the repair directory {repair_directory} : {e}")
+        })?;
+        let result = self
+            .repair_word_into(word, issues, &repair_directory, cli)
+            .await;
+        fs::remove_dir_all(&repair_directory)?;
+        result
+    }
+
+    async fn repair_word_into(
+        &self,
+        word: &str,
+        issues: &[Issue],
+        repair_directory: &str,
+        cli: &Cli,
+    ) -> Result<()> {
+        if issues
+            .iter()
+            .any(|i| matches!(i, Issue::Empty | Issue::InvalidJson(_) | Issue::MissingFile))
+        {
+            // the content cannot be trusted, download it again
+            Nearby::new(word.to_owned())
+                .generate_nearby_word(repair_directory, cli)
+                .await?;
+            fs::create_dir_all(&cli.words_directory)?;
+            Self::replace_file(word, repair_directory, cli)?;
+            cli.log_and_print(&format!("{word} : file downloaded again"), LogLevel::Info)?;
+        } else if issues.iter().any(|i| {
+            matches!(
+                i,
+                Issue::Duplicates(_) | Issue::OutOfRange(_) | Issue::Unsorted
+            )
+        }) {
+            let mut words = HashSet::new();
+            for w in WordGetter::get_cemantix_words_of_found_word(word, &cli.words_directory)? {
+                if w.is_valid() {
+                    CemantixWord::insert_best_known(&mut words, w);
+                }
+            }
+            WordGetter::save_cemantix_words_of_found_word(word, &words, repair_directory)?;
+            Self::replace_file(word, repair_directory, cli)?;
+            cli.log_and_print(
+                &format!("{word} : file rebuilt with {} words", words.len()),
+                LogLevel::Info,
+            )?;
+        } else if issues.iter().any(|i| matches!(i, Issue::NotInHistory)) {
+            cli.log_and_print(
+                &format!("{word} : not in the history, use --remove-orphans to remove its file"),
+                LogLevel::Warn,
+            )?;
+        } else {
+            cli.log_and_print(
+                &format!("{word} : cannot be repaired automatically"),
+                LogLevel::Warn,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::{collections::HashMap, fs};
+
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    cemantix_word::MAX_RANK,
+    stats::{jaccard, label_propagation},
+    words_getter::WordGetter,
+};
+
+use super::options::{Cli, LogLevel};
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Clusters {
+    /// Number of closest words of a found word considered as its neighbourhood
+    #[arg(short, long, default_value_t = MAX_RANK as usize)]
+    pub neighbourhood: usize,
+
+    /// Number of most similar found words linked to each found word
+    #[arg(short, long, default_value_t = 5)]
+    pub links: usize,
+
+    /// Write the clusters as JSON into this file
+    #[arg(short, long)]
+    pub json: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Cluster {
+    /// most central members
+    pub name: Vec<String>,
+    /// members sorted by centrality
+    pub members: Vec<String>,
+}
+
+impl Clusters {
+    /// groups the found words with a label propagation on the graph linking each found word
+    /// to the found words whose neighbourhoods overlap the most (Jaccard similarity)
+    pub fn compute_clusters(&self, cli: &Cli) -> Result<Vec<Cluster>> {
+        let mut found_words = Vec::new();
+        let mut neighbourhoods: Vec<Vec<usize>> = Vec::new();
+        let mut ids: HashMap<String, usize> = HashMap::new();
+        let mut all_found_words = WordGetter::get_all_found_word(&cli.words_directory)?;
+        all_found_words.sort();
+        for found_word in all_found_words {
+            let mut words = match WordGetter::get_cemantix_words_of_found_word(
+                &found_word,
+                &cli.words_directory,
+            ) {
+                Ok(w) => w,
+                Err(e) => {
+                    cli.log_and_print(&format!("Ignoring {found_word} : {e}"), LogLevel::Warn)?;
+                    continue;
+                }
+            };
+            words.sort();
+            words.reverse();
+            let mut neighbourhood = words
+                .into_iter()
+                .filter(|w| w.word != found_word)
+                .take(self.neighbourhood)
+                .map(|w| {
+                    let id = ids.len();
+                    *ids.entry(w.word).or_insert(id)
+                })
+                .collect::<Vec<usize>>();
+            neighbourhood.sort();
+            neighbourhood.dedup();
+            neighbourhoods.push(neighbourhood);
+            found_words.push(found_word);
+        }
+
+        let n = found_words.len();
+        let mut similarities = vec![vec![0.0_f32; n]; n];
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let s = jaccard(&neighbourhoods[i], &neighbourhoods[j]);
+                similarities[i][j] = s;
+                similarities[j][i] = s;
+            }
+        }
+
+        // graph of the most similar found words, made symmetric
+        let mut links: Vec<HashMap<usize, f32>> = vec![HashMap::new(); n];
+        for i in 0..n {
+            let mut closest = (0..n).filter(|j| *j != i).collect::<Vec<usize>>();
+            closest.sort_by(|a, b| similarities[i][*b].total_cmp(&similarities[i][*a]));
+            for j in closest.into_iter().take(self.links) {
+                if similarities[i][j] > 0.0 {
+                    links[i].insert(j, similarities[i][j]);
+                    links[j].insert(i, similarities[i][j]);
+                }
+            }
+        }
+
+        let labels = label_propagation(&links);
+
+        let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (i, label) in labels.iter().enumerate() {
+            groups.entry(*label).or_default().push(i);
+        }
+        let mut clusters = groups
+            .into_values()
+            .map(|members| {
+                let centrality =
+                    |i: usize| members.iter().map(|j| similarities[i][*j]).sum::<f32>();
+                let mut sorted = members.clone();
+                sorted.sort_by(|a, b| centrality(*b).total_cmp(&centrality(*a)));
+                Cluster {
+                    name: sorted
+                        .iter()
+                        .take(3)
+                        .map(|i| found_words[*i].to_owned())
+                        .collect(),
+                    members: sorted.iter().map(|i| found_words[*i].to_owned()).collect(),
+                }
+            })
+            .collect::<Vec<Cluster>>();
+        clusters.sort_by(|a, b| {
+            b.members
+                .len()
+                .cmp(&a.members.len())
+                .then(a.name.cmp(&b.name))
+        });
+        Ok(clusters)
+    }
+
+    pub async fn print_clusters(&self, cli: &Cli) -> Result<()> {
+        let clusters = self.compute_clusters(cli)?;
+        println!("{:>4} {:>5}  {:<40} members", "#", "size", "name");
+        for (i, cluster) in clusters.iter().enumerate() {
+            println!(
+                "{:>4} {:>5}  {:<40} {}",
+                i + 1,
+                cluster.members.len(),
+                cluster.name.join(", "),
+                cluster.members.join(" ")
+            );
+        }
+        if let Some(json) = self.json.as_ref() {
+            fs::write(json, serde_json::to_string(&clusters)?)?;
+        }
+        cli.log_and_print(
+            &format!("{} clusters found", clusters.len()),
+            LogLevel::Info,
+        )?;
+        Ok(())
+    }
+}
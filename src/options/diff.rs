@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::{
+    cemantix_word::{CemantixWord, MAX_RANK},
+    stats::{kendall, spearman},
+    words_getter::WordGetter,
+};
+
+use super::options::Cli;
+
+/// closest word along with its position in the neighbourhood
+pub type Neighbour = (usize, CemantixWord);
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Diff {
+    /// First found word, or the date (dd-mm-yyyy) it was found
+    pub word_a: String,
+
+    /// Second found word, or the date (dd-mm-yyyy) it was found
+    pub word_b: String,
+
+    /// Number of closest words of each found word compared
+    #[arg(short, long, default_value_t = MAX_RANK as usize)]
+    pub neighbourhood: usize,
+
+    /// Number of words printed in each table
+    #[arg(short, long, default_value_t = 30)]
+    pub limit: usize,
+}
+
+impl Diff {
+    /// `size` closest words of a found word, the closest first, with their position in the list
+    pub fn neighbourhood(word: &str, mut words: Vec<CemantixWord>, size: usize) -> Vec<Neighbour> {
+        words.sort();
+        words.reverse();
+        words
+            .into_iter()
+            .filter(|w| w.word != word)
+            .take(size)
+            .enumerate()
+            .map(|(i, w)| (i + 1, w))
+            .collect()
+    }
+
+    /// closest words of both neighbourhoods, in the order of `a`
+    pub fn shared<'a>(
+        a: &'a [Neighbour],
+        b: &'a [Neighbour],
+    ) -> Vec<(&'a Neighbour, &'a Neighbour)> {
+        let b_words = b
+            .iter()
+            .map(|e| (e.1.word.as_str(), e))
+            .collect::<HashMap<&str, &Neighbour>>();
+        a.iter()
+            .filter_map(|e| b_words.get(e.1.word.as_str()).map(|f| (e, *f)))
+            .collect()
+    }
+
+    fn load_neighbourhood(&self, word: &str, cli: &Cli) -> Result<Vec<Neighbour>> {
+        let words = WordGetter::get_cemantix_words_of_found_word(word, &cli.words_directory)?;
+        Ok(Self::neighbourhood(word, words, self.neighbourhood))
+    }
+
+    /// rank given by the game, the words outside of the top 1000 having none
+    fn rank(word: &CemantixWord) -> String {
+        if word.rank > 0 {
+            word.rank.to_string()
+        } else {
+            String::from("-")
+        }
+    }
+
+    fn print_unique(&self, word: &str, words: &[&Neighbour]) {
+        println!("\nOnly close to {word} : {} words", words.len());
+        for (_, w) in words.iter().take(self.limit) {
+            println!("  {:<20} {:>5} {:>8.4}", w.word, Self::rank(w), w.score);
+        }
+    }
+
+    pub async fn diff_words(&self, cli: &Cli) -> Result<()> {
+        let word_a = WordGetter::resolve_day(&self.word_a, &cli.word_history)?;
+        let word_b = WordGetter::resolve_day(&self.word_b, &cli.word_history)?;
+        let a = self.load_neighbourhood(&word_a, cli)?;
+        let b = self.load_neighbourhood(&word_b, cli)?;
+        let b_words = b
+            .iter()
+            .map(|e| (e.1.word.as_str(), e))
+            .collect::<HashMap<&str, &Neighbour>>();
+        let a_words = a
+            .iter()
+            .map(|e| (e.1.word.as_str(), e))
+            .collect::<HashMap<&str, &Neighbour>>();
+
+        let shared = Self::shared(&a, &b);
+        println!(
+            "Shared closest words : {} ({} / {} compared)",
+            shared.len(),
+            a.len(),
+            b.len()
+        );
+        println!(
+            "  {:<20} {:>8} {:>8} {:>8} {:>8}",
+            "word", &word_a, "score", &word_b, "score"
+        );
+        for ((_, wa), (_, wb)) in shared.iter().take(self.limit) {
+            println!(
+                "  {:<20} {:>8} {:>8.4} {:>8} {:>8.4}",
+                wa.word,
+                Self::rank(wa),
+                wa.score,
+                Self::rank(wb),
+                wb.score
+            );
+        }
+
+        self.print_unique(
+            &word_a,
+            &a.iter()
+                .filter(|e| !b_words.contains_key(e.1.word.as_str()))
+                .collect::<Vec<_>>(),
+        );
+        self.print_unique(
+            &word_b,
+            &b.iter()
+                .filter(|e| !a_words.contains_key(e.1.word.as_str()))
+                .collect::<Vec<_>>(),
+        );
+
+        let xs = shared.iter().map(|(e, _)| e.1.score).collect::<Vec<f32>>();
+        let ys = shared.iter().map(|(_, f)| f.1.score).collect::<Vec<f32>>();
+        let format = |c: Option<f32>| c.map_or(String::from("undefined"), |c| format!("{c:.4}"));
+        println!(
+            "\nCorrelation over the shared words : Spearman {}, Kendall {}",
+            format(spearman(&xs, &ys)),
+            format(kendall(&xs, &ys))
+        );
+        Ok(())
+    }
+}
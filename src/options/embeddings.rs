@@ -0,0 +1,83 @@
+use anyhow::Result;
+use clap::Args;
+
+use crate::embeddings::{
+    cache::EmbeddingsCache,
+    loader::{self, read_vocabulary, EmbeddingsFormat},
+};
+
+use super::options::{Cli, LogLevel};
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Embeddings {
+    #[command(subcommand)]
+    pub action: EmbeddingsAction,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, clap::Subcommand)]
+pub enum EmbeddingsAction {
+    /// Convert a word2vec or fastText file into the embeddings file of the working directory
+    Build(EmbeddingsBuild),
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct EmbeddingsBuild {
+    /// File of vectors to convert
+    pub source: String,
+
+    /// Format of the file, guessed from its extension otherwise (.bin is binary)
+    #[arg(short, long, value_enum)]
+    pub format: Option<EmbeddingsFormat>,
+
+    /// Keep only the words of this list, a word at each line, the words list of the game otherwise
+    #[arg(long)]
+    pub vocabulary: Option<String>,
+
+    /// Keep every word of the file
+    #[arg(short, long, default_value_t = false, conflicts_with = "vocabulary")]
+    pub all_words: bool,
+
+    /// Store the vectors as int8, four times smaller
+    #[arg(short, long, default_value_t = false)]
+    pub quantize: bool,
+}
+
+impl Embeddings {
+    pub async fn run(&self, cli: &Cli) -> Result<()> {
+        match &self.action {
+            EmbeddingsAction::Build(build) => build.build_embeddings(cli).await,
+        }
+    }
+}
+
+impl EmbeddingsBuild {
+    pub async fn build_embeddings(&self, cli: &Cli) -> Result<()> {
+        // the words list of the game is used by default, Cemantle having none
+        let list = match (
+            self.all_words,
+            self.vocabulary.as_deref(),
+            cli.game.words_list(),
+        ) {
+            (true, ..) | (false, None, None) => None,
+            (false, list, default) => Some(cli.words_list(list, default)?),
+        };
+        let vocabulary = list
+            .map(|l| read_vocabulary(&l).map_err(|e| anyhow::anyhow!("Cannot read {l} : {e}")))
+            .transpose()?;
+        let embeddings = loader::Embeddings::load(&self.source, self.format, vocabulary.as_ref())?;
+        if embeddings.is_empty() {
+            return Err(anyhow::anyhow!("No vector found in {}", self.source));
+        }
+        EmbeddingsCache::write(&embeddings, &cli.embeddings, self.quantize)?;
+        cli.log_and_print(
+            &format!(
+                "{} vectors of dimension {} written into {}",
+                embeddings.len(),
+                embeddings.dim,
+                cli.embeddings
+            ),
+            LogLevel::Info,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use chrono::Local;
+use clap::Args;
+use flate2::{write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::words_getter::WordGetter;
+
+use super::options::{Cli, LogLevel};
+
+pub const MANIFEST_NAME: &str = "manifest.json";
+/// name of the history inside an archive
+pub const ARCHIVE_HISTORY: &str = "words_history";
+/// name of the words directory inside an archive
+pub const ARCHIVE_WORDS_DIRECTORY: &str = "words_folder";
+/// name of the sessions directory inside an archive
+pub const ARCHIVE_SESSIONS_DIRECTORY: &str = "sessions";
+/// name of the index cache inside the archives of older versions, it is not exported anymore
+pub const ARCHIVE_INDEX: &str = "words_index";
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Manifest {
+    pub version: u32,
+    pub created: String,
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Export {
+    /// Destination archive (tar)
+    pub archive: String,
+
+    /// Compress the archive with gzip
+    #[arg(short, long, default_value_t = false)]
+    pub compress: bool,
+}
+
+impl Export {
+    /// returns the files to archive along with their path inside the archive
+    /// the caches (words index, embeddings and their index) are left out, they can be rebuilt
+    pub fn data_files(cli: &Cli) -> Result<Vec<(PathBuf, String)>> {
+        let mut files = Vec::new();
+        if PathBuf::from(&cli.word_history).try_exists()? {
+            files.push((
+                PathBuf::from(&cli.word_history),
+                ARCHIVE_HISTORY.to_string(),
+            ));
+        }
+        let mut found_words =
+            WordGetter::get_all_found_word(&cli.words_directory).unwrap_or_default();
+        found_words.sort();
+        for word in found_words {
+            files.push((
+                PathBuf::from(&cli.words_directory).join(&word),
+                format!("{ARCHIVE_WORDS_DIRECTORY}/{word}"),
+            ));
+        }
+        if let Ok(sessions) = WordGetter::get_all_found_word(&cli.sessions_directory) {
+            let mut sessions = sessions;
+            sessions.sort();
+            for word in sessions {
+                files.push((
+                    PathBuf::from(&cli.sessions_directory).join(&word),
+                    format!("{ARCHIVE_SESSIONS_DIRECTORY}/{word}"),
+                ));
+            }
+        }
+        Ok(files)
+    }
+
+    pub fn sha256(data: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(data))
+    }
+
+    pub async fn export_data(&self, cli: &Cli) -> Result<()> {
+        let mut contents = Vec::new();
+        for (path, name) in Self::data_files(cli)? {
+            contents.push((name, fs::read(path)?));
+        }
+        let manifest = Manifest {
+            version: MANIFEST_VERSION,
+            created: Local::now().to_rfc3339(),
+            files: contents
+                .iter()
+                .map(|(name, data)| ManifestEntry {
+                    path: name.to_owned(),
+                    size: data.len() as u64,
+                    sha256: Self::sha256(data),
+                })
+                .collect(),
+        };
+
+        let file = File::create(&self.archive)?;
+        if self.compress {
+            Self::write_archive(
+                GzEncoder::new(file, Compression::default()),
+                &manifest,
+                &contents,
+            )?
+            .finish()?;
+        } else {
+            Self::write_archive(file, &manifest, &contents)?;
+        }
+
+        cli.log_and_print(
+            &format!(
+                "{} files exported into {}",
+                manifest.files.len(),
+                self.archive
+            ),
+            LogLevel::Info,
+        )?;
+        Ok(())
+    }
+
+    fn write_archive<W: Write>(
+        writer: W,
+        manifest: &Manifest,
+        contents: &[(String, Vec<u8>)],
+    ) -> Result<W> {
+        let mut builder = tar::Builder::new(writer);
+        Self::append(
+            &mut builder,
+            MANIFEST_NAME,
+            &serde_json::to_vec_pretty(manifest)?,
+        )?;
+        for (name, data) in contents.iter() {
+            Self::append(&mut builder, name, data)?;
+        }
+        Ok(builder.into_inner()?)
+    }
+
+    fn append<W: Write>(builder: &mut tar::Builder<W>, name: &str, data: &[u8]) -> Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(Local::now().timestamp() as u64);
+        header.set_cksum();
+        Ok(builder.append_data(&mut header, name, data)?)
+    }
+}
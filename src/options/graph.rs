@@ -1,11 +1,13 @@
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, fs, sync::Arc};
 
 use anyhow::Result;
-use chrono::Local;
 use clap::Args;
 use futures::lock::Mutex;
 
-use crate::{cemantix_word::CemantixWord, utils::send_words, words_getter::WordGetter};
+use crate::{
+    cemantix_word::CemantixWord, day::puzzle_date, semantic_graph::SemanticGraph,
+    utils::send_words, words_getter::WordGetter,
+};
 
 use super::{
     options::{Cli, LogLevel},
@@ -17,11 +19,78 @@ pub struct Graph {
     /// Number of words in batches not over 200
     #[arg(short, long, default_value_t = 100)]
     pub batch_size: usize,
+
+    #[command(subcommand)]
+    pub action: Option<GraphAction>,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, clap::Subcommand)]
+pub enum GraphAction {
+    /// Export the graph of the found words and their closest words
+    Export(GraphExport),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, clap::ValueEnum)]
+pub enum GraphFormat {
+    Dot,
+    Graphml,
+    Json,
+}
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct GraphExport {
+    /// Format of the exported graph
+    #[arg(short, long, value_enum, default_value_t = GraphFormat::Dot)]
+    pub format: GraphFormat,
+
+    /// Destination file, standard output otherwise
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Keep only the closest words of each found word
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+}
+
+impl GraphExport {
+    pub async fn export_graph(&self, cli: &Cli) -> Result<()> {
+        let graph = SemanticGraph::from_words_directory(&cli.words_directory, self.limit, cli)?;
+        let data = match self.format {
+            GraphFormat::Dot => graph.to_dot(),
+            GraphFormat::Graphml => graph.to_graphml(),
+            GraphFormat::Json => graph.to_json()?,
+        };
+        match self.output.as_ref() {
+            Some(output) => {
+                fs::write(output, data)?;
+                cli.log_and_print(
+                    &format!(
+                        "Graph of {} words and {} edges written into {output}",
+                        graph.nodes.len(),
+                        graph.edges.len()
+                    ),
+                    LogLevel::Info,
+                )?;
+            }
+            None => println!("{data}"),
+        }
+        Ok(())
+    }
 }
 
 impl Graph {
     pub fn new(batch_size: usize) -> Self {
-        Self { batch_size }
+        Self {
+            batch_size,
+            action: None,
+        }
+    }
+
+    pub async fn run(&self, cli: &Cli) -> Result<()> {
+        match self.action.as_ref() {
+            Some(GraphAction::Export(export)) => export.export_graph(cli).await,
+            None => self.generate_graph(cli, None).await,
+        }
     }
 
     pub async fn generate_graph(
@@ -41,7 +110,7 @@ impl Graph {
         let last_word = WordGetter::get_last_found_word(&cli.word_history)?;
         let word: String;
         if let Some(last) = last_word {
-            if last.1 != Local::now().date_naive() {
+            if last.1 != puzzle_date() {
                 return Err(anyhow::anyhow!("Word of the day not found"));
             }
             word = last.0;
@@ -107,6 +176,7 @@ impl Graph {
         words_list.retain(|cw| !b.words_data.iter().any(|cw_wd| &&cw_wd.word == cw));
         let reduced_words_number = words_list.len();
         send_words(
+            &cli.endpoint,
             words_list.len(),
             words_list,
             self.batch_size,
@@ -114,7 +184,7 @@ impl Graph {
             callback_best,
             cli.verbose,
         )
-        .await;
+        .await?;
         cli.log_and_print(
             &format!(
                 "{} words have been tested and added to the file {} !",
@@ -0,0 +1,191 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{self, BufRead, BufReader, ErrorKind, Read, Write},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use clap::Args;
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+
+use crate::words_getter::WordGetter;
+
+use super::{
+    export::{
+        Manifest, ARCHIVE_HISTORY, ARCHIVE_INDEX, ARCHIVE_SESSIONS_DIRECTORY,
+        ARCHIVE_WORDS_DIRECTORY, MANIFEST_NAME,
+    },
+    options::{Cli, LogLevel},
+};
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Import {
+    /// Source archive (tar or tar.gz) created by the export command
+    pub archive: String,
+
+    /// Merge the archive into the existing data instead of restoring it
+    #[arg(short, long, default_value_t = false)]
+    pub merge: bool,
+}
+
+impl Import {
+    /// entries of an archive, read from the file as they come
+    fn open_archive(archive: &str) -> Result<tar::Archive<Box<dyn Read>>> {
+        let mut file = BufReader::new(File::open(archive)?);
+        let reader: Box<dyn Read> = if file.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+        Ok(tar::Archive::new(reader))
+    }
+
+    /// reads the manifest of an archive and checks every file against it, one file at a time
+    pub fn read_archive(archive: &str) -> Result<Manifest> {
+        let mut manifest = None;
+        let mut checksums = HashMap::new();
+        for entry in Self::open_archive(archive)?.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().to_string();
+            if path == MANIFEST_NAME {
+                let mut content = Vec::new();
+                entry.read_to_end(&mut content)?;
+                manifest = Some(serde_json::from_slice::<Manifest>(&content)?);
+            } else {
+                let mut hasher = Sha256::new();
+                io::copy(&mut entry, &mut hasher)?;
+                checksums.insert(path, format!("{:x}", hasher.finalize()));
+            }
+        }
+
+        let manifest =
+            manifest.ok_or_else(|| anyhow::anyhow!("{archive} has no {MANIFEST_NAME}"))?;
+        for entry in manifest.files.iter() {
+            let checksum = checksums
+                .get(&entry.path)
+                .ok_or_else(|| anyhow::anyhow!("{} is missing from {archive}", entry.path))?;
+            if *checksum != entry.sha256 {
+                return Err(anyhow::anyhow!("checksum mismatch for {}", entry.path));
+            }
+        }
+        Ok(manifest)
+    }
+
+    pub async fn import_data(&self, cli: &Cli) -> Result<()> {
+        // the archive is checked as a whole before anything is written
+        let manifest = Self::read_archive(&self.archive)?;
+        if !self.merge && PathBuf::from(&cli.word_history).try_exists()? {
+            return Err(anyhow::anyhow!(
+                "{} already exists, use --merge to import into existing data",
+                cli.word_history
+            ));
+        }
+        let paths = manifest
+            .files
+            .iter()
+            .map(|e| e.path.as_str())
+            .collect::<HashSet<&str>>();
+
+        fs::create_dir_all(&cli.working_directory)?;
+        let mut conflicts = 0;
+        for entry in Self::open_archive(&self.archive)?.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().to_string();
+            if !paths.contains(path.as_str()) {
+                if path != MANIFEST_NAME {
+                    cli.log_and_print(&format!("Ignoring unlisted entry {path}"), LogLevel::Warn)?;
+                }
+                continue;
+            }
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            let content = &content;
+            if path == ARCHIVE_HISTORY {
+                conflicts += self.import_history(content, cli)?;
+            } else if path == ARCHIVE_INDEX {
+                // the cache is rebuilt by lookup from the imported words directory
+                continue;
+            } else if let Some(word) = path
+                .strip_prefix(ARCHIVE_WORDS_DIRECTORY)
+                .and_then(|p| p.strip_prefix('/'))
+            {
+                if !Self::is_valid_name(word) {
+                    cli.log_and_print(&format!("Ignoring invalid entry {path}"), LogLevel::Warn)?;
+                    continue;
+                }
+                if !self.import_found_word(word, content, cli)? {
+                    conflicts += 1;
+                }
+            } else if let Some(word) = path
+                .strip_prefix(ARCHIVE_SESSIONS_DIRECTORY)
+                .and_then(|p| p.strip_prefix('/'))
+                .filter(|w| Self::is_valid_name(w))
+            {
+                // sessions of the local working directory are kept when merging
+                let path = PathBuf::from(&cli.sessions_directory).join(word);
+                if !self.merge || !path.try_exists()? {
+                    fs::create_dir_all(&cli.sessions_directory)?;
+                    fs::write(path, content)?;
+                }
+            } else {
+                cli.log_and_print(&format!("Ignoring unknown entry {path}"), LogLevel::Warn)?;
+            }
+        }
+
+        cli.log_and_print(
+            &format!(
+                "{} files imported from {} ({} conflicts)",
+                manifest.files.len(),
+                self.archive,
+                conflicts
+            ),
+            LogLevel::Info,
+        )?;
+        Ok(())
+    }
+
+    /// checks that the name of a file inside the archive cannot escape its directory
+    /// checks that a name read from outside stays inside the directory it is joined to
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty() && !name.contains(['/', '\\']) && !name.contains("..")
+    }
+
+    /// returns the number of conflicting dates
+    fn import_history(&self, content: &[u8], cli: &Cli) -> Result<usize> {
+        if !self.merge {
+            fs::write(&cli.word_history, content)?;
+            return Ok(0);
+        }
+        let other = WordGetter::parse_history(&String::from_utf8_lossy(content))?;
+        let (_, conflicts) = WordGetter::merge_history_file(other, "archive", cli)?;
+        Ok(conflicts)
+    }
+
+    /// returns false if a different file already exists for this word
+    fn import_found_word(&self, word: &str, content: &[u8], cli: &Cli) -> Result<bool> {
+        if !self.merge {
+            WordGetter::create_file_word(word, &cli.words_directory)?.write_all(content)?;
+            return Ok(true);
+        }
+        match WordGetter::get_file_word(word, true, true, false, &cli.words_directory) {
+            Ok(mut file) => {
+                file.write_all(content)?;
+                Ok(true)
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let local = fs::read(PathBuf::from(&cli.words_directory).join(word))?;
+                if local != content {
+                    cli.log_and_print(
+                        &format!("Conflict on {word} : local file differs, keeping it"),
+                        LogLevel::Warn,
+                    )?;
+                    return Ok(false);
+                }
+                Ok(true)
+            }
+            Err(e) => Err(anyhow::anyhow!(e)),
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, read_to_string},
+    path::PathBuf,
+    time::UNIX_EPOCH,
+};
+
+use anyhow::Result;
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use crate::words_getter::WordGetter;
+
+use super::options::{Cli, LogLevel};
+
+pub const DEFAULT_INDEX_FILENAME: &str = "words_index";
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Lookup {
+    /// Words to look for in the closest words of the found words
+    #[arg(required = true)]
+    pub words: Vec<String>,
+
+    /// Print the result as JSON
+    #[arg(short, long, default_value_t = false)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    /// found word in whose closest words the word appears
+    pub answer: String,
+    pub rank: isize,
+    pub score: f32,
+}
+
+/// inverted index of the words directory: word -> found words whose closest words contain it
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WordsIndex {
+    /// name, size and modification time of every file indexed
+    fingerprint: Vec<(String, u64, u64)>,
+    pub entries: HashMap<String, Vec<IndexEntry>>,
+}
+
+impl WordsIndex {
+    fn fingerprint(words_fcontainer_name: &str) -> Result<Vec<(String, u64, u64)>> {
+        let mut fingerprint = Vec::new();
+        for word in WordGetter::get_all_found_word(words_fcontainer_name)? {
+            let metadata = fs::metadata(PathBuf::from(words_fcontainer_name).join(&word))?;
+            let modified = metadata.modified()?.duration_since(UNIX_EPOCH)?.as_secs();
+            fingerprint.push((word, metadata.len(), modified));
+        }
+        fingerprint.sort();
+        Ok(fingerprint)
+    }
+
+    pub fn build(words_fcontainer_name: &str) -> Result<Self> {
+        let mut index = Self {
+            fingerprint: Self::fingerprint(words_fcontainer_name)?,
+            entries: HashMap::new(),
+        };
+        for (answer, ..) in index.fingerprint.iter() {
+            let Ok(words) =
+                WordGetter::get_cemantix_words_of_found_word(answer, words_fcontainer_name)
+            else {
+                continue;
+            };
+            for w in words.into_iter().filter(|w| &w.word != answer) {
+                index.entries.entry(w.word).or_default().push(IndexEntry {
+                    answer: answer.to_owned(),
+                    rank: w.rank,
+                    score: w.score,
+                });
+            }
+        }
+        for entries in index.entries.values_mut() {
+            entries.sort_by(|a, b| b.score.total_cmp(&a.score));
+        }
+        Ok(index)
+    }
+
+    /// loads the cached index, rebuilding it if the words directory changed since
+    pub fn load(index_filename: &str, words_fcontainer_name: &str, cli: &Cli) -> Result<Self> {
+        if let Ok(index) = read_to_string(index_filename)
+            .map_err(anyhow::Error::from)
+            .and_then(|c| Ok(serde_json::from_str::<Self>(&c)?))
+        {
+            if index.fingerprint == Self::fingerprint(words_fcontainer_name)? {
+                return Ok(index);
+            }
+        }
+        cli.log_and_print("Building the index of the words directory", LogLevel::Info)?;
+        let index = Self::build(words_fcontainer_name)?;
+        fs::write(index_filename, serde_json::to_string(&index)?)?;
+        Ok(index)
+    }
+}
+
+impl Lookup {
+    pub async fn lookup_words(&self, cli: &Cli) -> Result<()> {
+        let index = WordsIndex::load(&cli.words_index, &cli.words_directory, cli)?;
+        let results = self
+            .words
+            .iter()
+            .map(|w| (w, index.entries.get(w).cloned().unwrap_or_default()))
+            .collect::<BTreeMap<&String, Vec<IndexEntry>>>();
+
+        if self.json {
+            println!("{}", serde_json::to_string(&results)?);
+            return Ok(());
+        }
+        for word in self.words.iter() {
+            let entries = &results[word];
+            println!("{word} : close to {} found words", entries.len());
+            for e in entries.iter() {
+                println!("  {:<20} {:>5} {:>8.4}", e.answer, e.rank, e.score);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use std::{collections::HashMap, fs};
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::{
+    stats::{jaccard, label_propagation},
+    words_getter::WordGetter,
+};
+
+use super::{
+    lookup::WordsIndex,
+    options::{Cli, LogLevel},
+};
+
+const WIDTH: f32 = 1000.0;
+const MARGIN: f32 = 60.0;
+const PALETTE: [&str; 10] = [
+    "#1f77b4", "#2ca02c", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f", "#bcbd22", "#17becf",
+    "#ff7f0e", "#aec7e8",
+];
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Map {
+    /// Found word at the center of the map
+    pub word: String,
+
+    /// Number of closest words drawn
+    #[arg(short, long, default_value_t = 100)]
+    pub size: usize,
+
+    /// Destination file, <word>.svg otherwise
+    #[arg(short, long)]
+    pub output: Option<String>,
+}
+
+impl Map {
+    /// computes a force-directed layout where the found word is linked to its closest words
+    /// and closest words are linked to each other according to `similarities`
+    /// returns the position of each word, the found word first
+    pub fn layout(scores: &[f32], similarities: &[Vec<f32>]) -> Vec<(f32, f32)> {
+        let n = scores.len() + 1;
+        // the found word is the node 0, placed at the center
+        let mut positions = vec![(0.0_f32, 0.0_f32); n];
+        let golden_angle = std::f32::consts::PI * (3.0 - 5.0_f32.sqrt());
+        for (i, score) in scores.iter().enumerate() {
+            let angle = golden_angle * i as f32;
+            let radius = 1.0 - score;
+            positions[i + 1] = (radius * angle.cos(), radius * angle.sin());
+        }
+
+        let iterations = 300;
+        for step in 0..iterations {
+            let temperature = 0.05 * (1.0 - step as f32 / iterations as f32);
+            let mut moves = vec![(0.0_f32, 0.0_f32); n];
+            for i in 1..n {
+                for j in 0..n {
+                    if i == j {
+                        continue;
+                    }
+                    let (dx, dy) = (
+                        positions[i].0 - positions[j].0,
+                        positions[i].1 - positions[j].1,
+                    );
+                    let distance = (dx * dx + dy * dy).sqrt().max(1e-3);
+                    // target length and stiffness of the spring between i and j, if any
+                    let spring = if j == 0 {
+                        Some((1.0 - scores[i - 1], 1.0))
+                    } else {
+                        let s = similarities[i - 1][j - 1];
+                        (s > 0.0).then_some((1.0 - s, s))
+                    };
+                    let mut force = 0.002 / (distance * distance);
+                    if let Some((length, stiffness)) = spring {
+                        force += stiffness * (length - distance);
+                    }
+                    moves[i].0 += force * dx / distance;
+                    moves[i].1 += force * dy / distance;
+                }
+            }
+            for i in 1..n {
+                let (mx, my) = moves[i];
+                let norm = (mx * mx + my * my).sqrt();
+                if norm > 0.0 {
+                    let step = norm.min(temperature);
+                    positions[i].0 += mx / norm * step;
+                    positions[i].1 += my / norm * step;
+                }
+            }
+        }
+        positions
+    }
+
+    pub fn render_svg(words: &[String], positions: &[(f32, f32)], groups: &[usize]) -> String {
+        let extent = positions
+            .iter()
+            .map(|(x, y)| x.abs().max(y.abs()))
+            .fold(1e-3_f32, f32::max);
+        let project = |(x, y): (f32, f32)| {
+            (
+                WIDTH / 2.0 + x / extent * (WIDTH / 2.0 - MARGIN),
+                WIDTH / 2.0 + y / extent * (WIDTH / 2.0 - MARGIN),
+            )
+        };
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        };
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{WIDTH}\" viewBox=\"0 0 {WIDTH} {WIDTH}\">\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n"
+        );
+        let (cx, cy) = project(positions[0]);
+        for p in positions.iter().skip(1) {
+            let (x, y) = project(*p);
+            svg += &format!(
+                "<line x1=\"{cx:.1}\" y1=\"{cy:.1}\" x2=\"{x:.1}\" y2=\"{y:.1}\" stroke=\"#dddddd\" stroke-width=\"0.5\"/>\n"
+            );
+        }
+        for (i, (word, p)) in words.iter().zip(positions.iter()).enumerate().rev() {
+            let (x, y) = project(*p);
+            let (colour, radius, size) = if i == 0 {
+                ("#d62728", 9.0, 18)
+            } else {
+                (PALETTE[groups[i - 1] % PALETTE.len()], 4.0, 11)
+            };
+            svg += &format!(
+                "<circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"{radius}\" fill=\"{colour}\"/>\n\
+                 <text x=\"{:.1}\" y=\"{y:.1}\" font-family=\"sans-serif\" font-size=\"{size}\" fill=\"{colour}\" dominant-baseline=\"middle\">{}</text>\n",
+                x + radius + 2.0,
+                escape(word)
+            );
+        }
+        svg += "</svg>\n";
+        svg
+    }
+
+    pub async fn generate_map(&self, cli: &Cli) -> Result<()> {
+        let mut words =
+            WordGetter::get_cemantix_words_of_found_word(&self.word, &cli.words_directory)?;
+        words.sort();
+        words.reverse();
+        words.retain(|w| w.word != self.word);
+        words.truncate(self.size);
+
+        // closest words are similar when they appear around the same other found words
+        let index = WordsIndex::load(&cli.words_index, &cli.words_directory, cli)?;
+        let answers = words
+            .iter()
+            .map(|w| {
+                let mut answers = index
+                    .entries
+                    .get(&w.word)
+                    .map(|e| {
+                        e.iter()
+                            .filter(|e| e.answer != self.word)
+                            .map(|e| e.answer.as_str())
+                            .collect::<Vec<&str>>()
+                    })
+                    .unwrap_or_default();
+                answers.sort();
+                answers
+            })
+            .collect::<Vec<Vec<&str>>>();
+        let similarities = answers
+            .iter()
+            .map(|a| answers.iter().map(|b| jaccard(a, b)).collect())
+            .collect::<Vec<Vec<f32>>>();
+
+        let links = similarities
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(j, s)| *j != i && **s > 0.0)
+                    .map(|(j, s)| (j, *s))
+                    .collect::<HashMap<usize, f32>>()
+            })
+            .collect::<Vec<HashMap<usize, f32>>>();
+        let labels = label_propagation(&links);
+        let mut group_ids: HashMap<usize, usize> = HashMap::new();
+        let groups = labels
+            .iter()
+            .map(|l| {
+                let next = group_ids.len();
+                *group_ids.entry(*l).or_insert(next)
+            })
+            .collect::<Vec<usize>>();
+
+        let positions = Self::layout(
+            &words.iter().map(|w| w.score).collect::<Vec<f32>>(),
+            &similarities,
+        );
+        let names = std::iter::once(self.word.to_owned())
+            .chain(words.iter().map(|w| w.word.to_owned()))
+            .collect::<Vec<String>>();
+        let output = self
+            .output
+            .to_owned()
+            .unwrap_or_else(|| format!("{}.svg", self.word));
+        fs::write(&output, Self::render_svg(&names, &positions, &groups))?;
+        cli.log_and_print(
+            &format!("Map of {} words written into {output}", names.len()),
+            LogLevel::Info,
+        )?;
+        Ok(())
+    }
+}
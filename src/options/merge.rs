@@ -0,0 +1,119 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::{cemantix_word::CemantixWord, words_getter::WordGetter};
+
+use super::{
+    extend::Extend,
+    options::{Cli, LogLevel},
+};
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Merge {
+    /// Working directory of a teammate
+    pub other_working_directory: String,
+
+    /// the history of the other working directory, the one of the game otherwise
+    #[arg(long)]
+    pub other_word_history: Option<String>,
+
+    /// the directory that contains the words found in the other working directory, the one of the game otherwise
+    #[arg(long)]
+    pub other_words_directory: Option<String>,
+
+    /// words list extended with the words learned from the merge
+    #[arg(long)]
+    pub words_list_file: Option<String>,
+}
+
+impl Merge {
+    pub async fn merge_data(&self, cli: &Cli) -> Result<()> {
+        let other = PathBuf::from(&self.other_working_directory);
+        let other_history = other
+            .join(
+                self.other_word_history
+                    .as_deref()
+                    .unwrap_or(cli.game.history_filename()),
+            )
+            .display()
+            .to_string();
+        let other_words_directory = other
+            .join(
+                self.other_words_directory
+                    .as_deref()
+                    .unwrap_or(cli.game.words_folder()),
+            )
+            .display()
+            .to_string();
+
+        self.merge_history(&other_history, cli)?;
+        self.merge_found_words(&other_words_directory, cli)?;
+
+        if let Some(words_list_file) = self.words_list_file.as_ref() {
+            Extend::new(words_list_file.to_owned())
+                .extend_file(&cli.words_directory)
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn merge_history(&self, other_history: &str, cli: &Cli) -> Result<()> {
+        let other = WordGetter::get_history(other_history)
+            .map_err(|e| anyhow::anyhow!("Cannot read {other_history} : {e}"))?;
+        let (added, conflicts) = WordGetter::merge_history_file(other, "other", cli)?;
+        cli.log_and_print(
+            &format!("{added} days added to the history ({conflicts} conflicts)"),
+            LogLevel::Info,
+        )?;
+        Ok(())
+    }
+
+    fn merge_found_words(&self, other_words_directory: &str, cli: &Cli) -> Result<()> {
+        let mut merged = 0;
+        let mut new_words = 0;
+        for word in WordGetter::get_all_found_word(other_words_directory)? {
+            let other_words =
+                match WordGetter::get_cemantix_words_of_found_word(&word, other_words_directory) {
+                    Ok(w) => w,
+                    Err(e) => {
+                        cli.log_and_print(
+                            &format!("Ignoring {word} of {other_words_directory} : {e}"),
+                            LogLevel::Warn,
+                        )?;
+                        continue;
+                    }
+                };
+            let mut words = HashSet::new();
+            if PathBuf::from(&cli.words_directory)
+                .join(&word)
+                .try_exists()?
+            {
+                match WordGetter::get_cemantix_words_of_found_word(&word, &cli.words_directory) {
+                    Ok(local) => words.extend(local),
+                    Err(e) => cli.log_and_print(
+                        &format!("Replacing invalid local file {word} : {e}"),
+                        LogLevel::Warn,
+                    )?,
+                }
+            }
+
+            let len = words.len();
+            for w in other_words {
+                CemantixWord::insert_best_known(&mut words, w);
+            }
+            WordGetter::save_cemantix_words_of_found_word(&word, &words, &cli.words_directory)?;
+            if cli.verbose {
+                println!("{word} : {} new words", words.len() - len);
+            }
+            new_words += words.len() - len;
+            merged += 1;
+        }
+        cli.log_and_print(
+            &format!("{merged} found words merged, {new_words} new words"),
+            LogLevel::Info,
+        )?;
+        Ok(())
+    }
+}
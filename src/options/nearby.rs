@@ -1,72 +1,130 @@
-use std::io::{ErrorKind, Write};
-
 use anyhow::Result;
 use clap::Args;
 
-use crate::words_getter::WordGetter;
+use crate::{cemantix_word::CemantixWord, config::GameEndpoint, words_getter::WordGetter};
 
 use super::options::{Cli, LogLevel};
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
 pub struct Nearby {
     /// The word of the day only
-    pub word: String,
+    #[arg(required_unless_present = "all")]
+    pub word: Option<String>,
+
+    /// Fetch the closest words of every word of the history whose file is missing or invalid
+    #[arg(short, long, default_value_t = false, conflicts_with = "word")]
+    pub all: bool,
+
+    /// Overwrite the files that already exist
+    #[arg(short, long, default_value_t = false)]
+    pub force: bool,
 }
 
 impl Nearby {
     pub fn new(word: String) -> Self {
-        Self { word }
+        Self {
+            word: Some(word),
+            all: false,
+            force: false,
+        }
     }
 
-    pub async fn get_nearby(&self) -> anyhow::Result<String> {
+    pub async fn get_nearby(endpoint: &GameEndpoint, word: &str) -> anyhow::Result<String> {
         let client = reqwest::Client::new();
-        let params = [("word", &self.word)];
+        let params = [("word", word)];
 
         let a = client
-            .post("https://cemantix.certitudes.org/nearby")
+            .post(endpoint.nearby_url())
             .form(&params)
             .header("Content-type", "application/x-www-form-urlencoded");
 
-        Ok(a.send().await?.text().await?)
+        let response = a.send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Unexpected status : {}", response.status()));
+        }
+        Ok(response.text().await?)
+    }
+
+    /// returns true if the file of the word exists and contains words
+    fn is_generated(word: &str, words_dir: &str) -> bool {
+        WordGetter::get_cemantix_words_of_found_word(word, words_dir).is_ok_and(|w| !w.is_empty())
     }
 
     pub async fn generate_nearby_word(&self, words_dir: &str, cli: &Cli) -> Result<()> {
-        let file_content = self.get_nearby().await?;
-        if file_content.is_empty() {
-            return Err(anyhow::anyhow!(
-                "Impossible de récupérer les mots proches de {}",
-                self.word
-            ));
+        if !self.all {
+            let word = self
+                .word
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("Please provide a word or --all"))?;
+            if !self.force && Self::is_generated(word, words_dir) {
+                return Err(anyhow::anyhow!("Already generated"));
+            }
+            return Self::save_nearby_word(word, words_dir, cli).await;
         }
-        let mut file_word =
-            match WordGetter::get_file_word(&self.word, true, true, false, words_dir) {
-                Ok(f) => f,
+
+        let (mut fetched, mut failed) = (0, 0);
+        for (word, _) in WordGetter::get_history(&cli.word_history)? {
+            if !self.force && Self::is_generated(&word, words_dir) {
+                continue;
+            }
+            match Self::save_nearby_word(&word, words_dir, cli).await {
+                Ok(()) => fetched += 1,
                 Err(e) => {
-                    match e.kind() {
-                        ErrorKind::NotFound => {
-                            return Err(anyhow::anyhow!(e));
-                        }
-                        ErrorKind::AlreadyExists => {
-                            return Err(anyhow::anyhow!("Already generated"));
-                        }
-                        _ => {}
-                    }
-                    return Ok(());
+                    failed += 1;
+                    cli.log_and_print(&format!("{word} : {e}"), LogLevel::Error)?;
                 }
-            };
-
-        if let Err(e) = file_word.write(&file_content.as_bytes()) {
-            cli.log_and_print(
-                &format!("cannot write data to file '{}' ({e})", self.word),
-                LogLevel::Error,
-            )?;
-        } else {
-            cli.log_and_print(
-                &format!("Successfully writen data into file '{}'", self.word),
-                LogLevel::Info,
-            )?;
+            }
         }
-
+        cli.log_and_print(
+            &format!("{fetched} files fetched, {failed} failures"),
+            LogLevel::Info,
+        )?;
         Ok(())
     }
+
+    /// parses the response of the nearby endpoint, sorted by rank
+    pub fn parse_nearby(body: &str) -> Result<Vec<CemantixWord>> {
+        let value: serde_json::Value = serde_json::from_str(body).map_err(|_| {
+            anyhow::anyhow!(
+                "unexpected response : {}",
+                body.chars().take(80).collect::<String>()
+            )
+        })?;
+        if let Some(error) = value.get("error") {
+            return Err(anyhow::anyhow!("server error : {error}"));
+        }
+
+        let mut words = serde_json::from_value::<Vec<CemantixWord>>(value)?;
+        if words.is_empty() {
+            return Err(anyhow::anyhow!("no words returned"));
+        }
+        if let Some(w) = words.iter().find(|w| !w.is_valid()) {
+            return Err(anyhow::anyhow!(
+                "invalid word {} (rank {}, score {})",
+                w.word,
+                w.rank,
+                w.score
+            ));
+        }
+        words.sort_by_key(|w| w.rank);
+        if words.windows(2).any(|w| w[0].score > w[1].score) {
+            return Err(anyhow::anyhow!("ranks do not match scores"));
+        }
+        Ok(words)
+    }
+
+    /// downloads the closest words of `word` and writes them once they are known to be valid
+    async fn save_nearby_word(word: &str, words_dir: &str, cli: &Cli) -> Result<()> {
+        let words =
+            Self::parse_nearby(&Self::get_nearby(&cli.endpoint, word).await?).map_err(|e| {
+                anyhow::anyhow!("Impossible de récupérer les mots proches de {} ({e})", word)
+            })?;
+
+        WordGetter::save_cemantix_words_of_found_word(word, &words, words_dir)
+            .map_err(|e| anyhow::anyhow!("cannot write data to file '{}' ({e})", word))?;
+        cli.log_and_print(
+            &format!("Successfully writen data into file '{}'", word),
+            LogLevel::Info,
+        )
+    }
 }
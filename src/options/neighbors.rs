@@ -0,0 +1,90 @@
+use std::{fs, time::UNIX_EPOCH};
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::{
+    cemantix_word::CemantixWord,
+    embeddings::{cache::EmbeddingsCache, hnsw::Hnsw},
+};
+
+use super::options::{Cli, LogLevel};
+
+/// maximum number of neighbours of a node of the index
+const INDEX_LINKS: usize = 16;
+const INDEX_EF_CONSTRUCTION: usize = 100;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Neighbors {
+    /// Word whose most similar words are looked for in the embeddings
+    pub word: String,
+
+    /// Number of words printed
+    #[arg(short, default_value_t = 50)]
+    pub k: usize,
+
+    /// Number of candidates explored, higher is slower but more accurate
+    #[arg(long, default_value_t = 100)]
+    pub ef: usize,
+}
+
+impl Neighbors {
+    /// index of the embeddings, stored next to the embeddings file
+    pub fn index_filename(cli: &Cli) -> String {
+        format!("{}.hnsw", cli.embeddings)
+    }
+
+    /// loads the index of the embeddings, rebuilding it if the embeddings file changed since
+    pub fn load_index(cache: &EmbeddingsCache, cli: &Cli) -> Result<Hnsw> {
+        let metadata = fs::metadata(&cli.embeddings)?;
+        let fingerprint = (
+            metadata.len(),
+            metadata.modified()?.duration_since(UNIX_EPOCH)?.as_secs(),
+        );
+        let index_filename = Self::index_filename(cli);
+        if let Ok(index) = Hnsw::load(&index_filename) {
+            if index.fingerprint == fingerprint && index.len() == cache.len() {
+                return Ok(index);
+            }
+        }
+        cli.log_and_print("Building the index of the embeddings", LogLevel::Info)?;
+        let mut index = Hnsw::build(
+            cache.len(),
+            |a, b| cache.similarity(a, b),
+            INDEX_LINKS,
+            INDEX_EF_CONSTRUCTION,
+        );
+        index.fingerprint = fingerprint;
+        index.save(&index_filename)?;
+        Ok(index)
+    }
+
+    /// most similar words to a normalised vector, with a rank 0 as the game did not give it
+    pub fn similar_words(
+        cache: &EmbeddingsCache,
+        index: &Hnsw,
+        vector: &[f32],
+        k: usize,
+        ef: usize,
+    ) -> Vec<CemantixWord> {
+        index
+            .search(|i| cache.similarity_to(vector, i), k, ef)
+            .into_iter()
+            .map(|(i, score)| CemantixWord::new(cache.words[i].to_owned(), 0, score))
+            .collect()
+    }
+
+    pub async fn print_neighbors(&self, cli: &Cli) -> Result<()> {
+        let cache = EmbeddingsCache::open(&cli.embeddings)?;
+        let Some(i) = cache.index(&self.word) else {
+            return Err(anyhow::anyhow!("{} is not in the embeddings", self.word));
+        };
+        let index = Self::load_index(&cache, cli)?;
+        let mut words = Self::similar_words(&cache, &index, &cache.vector(i), self.k + 1, self.ef);
+        words.retain(|w| w.word != self.word);
+        words.truncate(self.k);
+        words.sort();
+        println!("{}", serde_json::to_string(&words)?);
+        Ok(())
+    }
+}
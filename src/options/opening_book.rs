@@ -0,0 +1,121 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::{cemantix_word::MAX_RANK, words_getter::WordGetter};
+
+use super::options::{Cli, LogLevel};
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct OpeningBook {
+    /// Destination file in the working directory, a word at each line
+    #[arg(short, long, default_value_t = String::from("opening_book"))]
+    pub output: String,
+
+    /// Number of words of the opening book
+    #[arg(short, long, default_value_t = 50)]
+    pub size: usize,
+
+    /// Number of closest words of a found word considered as its neighbourhood
+    #[arg(short, long, default_value_t = MAX_RANK as usize)]
+    pub neighbourhood: usize,
+}
+
+/// how a word appears in the neighbourhoods of the found words
+#[derive(Debug, Default)]
+struct Centrality {
+    /// score of the word for each found word whose neighbourhood contains it
+    scores: HashMap<usize, f32>,
+}
+
+impl OpeningBook {
+    pub async fn generate_opening_book(&self, cli: &Cli) -> Result<()> {
+        let found_words = WordGetter::get_all_found_word(&cli.words_directory)?;
+        let mut centralities: HashMap<String, Centrality> = HashMap::new();
+        for (i, found_word) in found_words.iter().enumerate() {
+            let mut words = match WordGetter::get_cemantix_words_of_found_word(
+                found_word,
+                &cli.words_directory,
+            ) {
+                Ok(w) => w,
+                Err(e) => {
+                    cli.log_and_print(&format!("Ignoring {found_word} : {e}"), LogLevel::Warn)?;
+                    continue;
+                }
+            };
+            words.sort();
+            words.reverse();
+            for w in words
+                .into_iter()
+                .filter(|w| &w.word != found_word)
+                .take(self.neighbourhood)
+            {
+                centralities
+                    .entry(w.word)
+                    .or_default()
+                    .scores
+                    .insert(i, w.score);
+            }
+        }
+
+        // greedy selection maximising the sum, over the found words, of the best score of the book
+        // a word close to found words already covered by the book brings little
+        let mut best_scores = vec![0.0_f32; found_words.len()];
+        let mut book: Vec<(String, f32, usize, f32)> = Vec::with_capacity(self.size);
+        while book.len() < self.size {
+            let gain = |c: &Centrality| {
+                c.scores
+                    .iter()
+                    .map(|(i, s)| (s - best_scores[*i]).max(0.0))
+                    .sum::<f32>()
+            };
+            let Some((word, centrality)) = centralities
+                .iter()
+                .max_by(|x, y| gain(x.1).total_cmp(&gain(y.1)).then(y.0.cmp(x.0)))
+            else {
+                break;
+            };
+            let g = gain(centrality);
+            if g <= 0.0 {
+                break;
+            }
+            for (i, s) in centrality.scores.iter() {
+                best_scores[*i] = best_scores[*i].max(*s);
+            }
+            let count = centrality.scores.len();
+            let mean = centrality.scores.values().sum::<f32>() / count as f32;
+            let word = word.to_owned();
+            centralities.remove(&word);
+            book.push((word, g, count, mean));
+        }
+
+        if cli.verbose {
+            println!(
+                "{:<20} {:>8} {:>6} {:>10}",
+                "word", "gain", "count", "mean score"
+            );
+            for (word, gain, count, mean) in book.iter() {
+                println!("{word:<20} {gain:>8.3} {count:>6} {mean:>10.3}");
+            }
+        }
+        let covered = best_scores.iter().filter(|s| **s > 0.0).count();
+        let output = PathBuf::from(&cli.working_directory).join(&self.output);
+        fs::write(
+            &output,
+            book.iter()
+                .map(|(w, ..)| w.to_owned() + "\n")
+                .collect::<String>(),
+        )?;
+        cli.log_and_print(
+            &format!(
+                "{} words written into {}, covering {covered}/{} found words",
+                book.len(),
+                output.display(),
+                found_words.len()
+            ),
+            LogLevel::Info,
+        )?;
+        Ok(())
+    }
+}
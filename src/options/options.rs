@@ -2,11 +2,16 @@ use anyhow::Result;
 use std::{fmt::Display, fs::OpenOptions, io::Write, path::PathBuf};
 
 use chrono::Local;
-use clap::{command, Parser};
+use clap::{command, Args, Parser};
+
+use crate::config::{Config, Game, GameEndpoint, DEFAULT_CONFIG_FILENAME};
 
 use super::{
-    extend::Extend, graph::Graph, nearby::Nearby, remove_useless_words::Ruw, solve::Solve,
-    sort::Sort,
+    analyze::Analyze, calibrate::Calibrate, check::Check, clusters::Clusters, diff::Diff,
+    embeddings::Embeddings, export::Export, extend::Extend, graph::Graph, import::Import,
+    lookup::Lookup, map::Map, merge::Merge, nearby::Nearby, neighbors::Neighbors,
+    opening_book::OpeningBook, path::Path, pedantix::Pedantix, play::Play,
+    remove_useless_words::Ruw, report::Report, serve::Serve, solve::Solve, sort::Sort,
 };
 
 pub enum LogLevel {
@@ -25,8 +30,11 @@ impl ToString for LogLevel {
     }
 }
 
-const DEFAULT_HISTORY_FILENAME: &str = "words_history";
-const DEFAULT_WORDS_FOLDER: &str = "words_folder/";
+pub const DEFAULT_HISTORY_FILENAME: &str = "words_history";
+pub const DEFAULT_WORDS_FOLDER: &str = "words_folder/";
+pub const DEFAULT_SESSIONS_FOLDER: &str = "sessions/";
+pub const DEFAULT_EMBEDDINGS_FILENAME: &str = "embeddings.bin";
+pub const DEFAULT_SCORE_MODEL_FILENAME: &str = "score_model.json";
 const LOG_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, clap::Subcommand, Debug)]
@@ -41,8 +49,47 @@ pub enum Commands {
     Extend(Extend),
     /// Sort your file (A->Z)
     Sort(Sort),
-    /// Graph
+    /// Collect the scores of the closest words of every found word, or export them as a graph
     Graph(Graph),
+    /// Check the integrity of the history and of the words directory
+    Check(Check),
+    /// Export the history, the found words and the sessions into an archive
+    ///
+    /// The caches are not exported, they are rebuilt from the exported data or from their source:
+    /// the words index by lookup, the embeddings by `embeddings build` and their index by neighbors
+    Export(Export),
+    /// Import an archive created by the export command
+    Import(Import),
+    /// Merge the data of another working directory
+    Merge(Merge),
+    /// Find the most similar chain of words between two words
+    Path(Path),
+    /// Generate the first words to test from the closest words of the found words
+    OpeningBook(OpeningBook),
+    /// Group the found words by the overlap of their closest words
+    Clusters(Clusters),
+    /// Find the found words whose closest words contain the given words
+    Lookup(Lookup),
+    /// Draw a map of the closest words of a found word into an SVG file
+    Map(Map),
+    /// Generate an HTML page for each found word
+    Report(Report),
+    /// Analyse the distribution of the scores of the found words
+    Analyze(Analyze),
+    /// Compare the closest words of two found words
+    Diff(Diff),
+    /// Manage the word vectors used to score words offline
+    Embeddings(Embeddings),
+    /// Find the most similar words of a word in the embeddings
+    Neighbors(Neighbors),
+    /// Learn how to turn the similarities of the embeddings into scores
+    Calibrate(Calibrate),
+    /// Play offline with a random word or a past word
+    Play(Play),
+    /// Run a local server answering like the game
+    Serve(Serve),
+    /// Reveal the page of the day of Pedantix
+    Pedantix(Pedantix),
 }
 
 impl Display for Commands {
@@ -54,6 +101,24 @@ impl Display for Commands {
             Commands::Extend(_) => "Extend",
             Commands::Sort(_) => "Sort",
             Commands::Graph(_) => "Graph",
+            Commands::Check(_) => "Check",
+            Commands::Export(_) => "Export",
+            Commands::Import(_) => "Import",
+            Commands::Merge(_) => "Merge",
+            Commands::Path(_) => "Path",
+            Commands::OpeningBook(_) => "Opening book",
+            Commands::Clusters(_) => "Clusters",
+            Commands::Lookup(_) => "Lookup",
+            Commands::Map(_) => "Map",
+            Commands::Report(_) => "Report",
+            Commands::Analyze(_) => "Analyze",
+            Commands::Diff(_) => "Diff",
+            Commands::Embeddings(_) => "Embeddings",
+            Commands::Neighbors(_) => "Neighbors",
+            Commands::Calibrate(_) => "Calibrate",
+            Commands::Play(_) => "Play",
+            Commands::Serve(_) => "Serve",
+            Commands::Pedantix(_) => "Pedantix",
         })
     }
 }
@@ -70,14 +135,53 @@ pub struct Cli {
     #[arg(short, long, default_value_t = false)]
     pub verbose: bool,
 
+    /// the game played, which selects the server, the words list and the files used by default
+    #[arg(long, value_enum, default_value_t = Game::Cemantix)]
+    pub game: Game,
+
+    #[command(flatten)]
+    files: DataFiles,
+
     /// the directory that contains all the words found (nearby)
-    #[arg(long, default_value_t = String::from(DEFAULT_WORDS_FOLDER))]
+    #[arg(skip)]
     pub words_directory: String,
 
-    /// declare the name of the file in which the history of words found will be written
-    #[arg(long, default_value_t = String::from(DEFAULT_HISTORY_FILENAME))]
+    /// the file in which the history of words found is written
+    #[arg(skip)]
     pub word_history: String,
 
+    /// the directory that contains the record of each resolution
+    #[arg(skip)]
+    pub sessions_directory: String,
+
+    /// the cache of the index of the words directory used by lookup and map
+    #[arg(skip)]
+    pub words_index: String,
+
+    /// the file of word vectors built by the embeddings command
+    #[arg(skip)]
+    pub embeddings: String,
+
+    /// the model turning the similarities of the embeddings into scores
+    #[arg(skip)]
+    pub score_model: String,
+
+    /// the server answering the requests, the serve command runs a local one
+    #[arg(long)]
+    pub base_url: Option<String>,
+
+    /// the Origin header of the requests, the server otherwise
+    #[arg(long)]
+    pub origin: Option<String>,
+
+    /// the configuration file, its endpoint being overridden by --base-url and --origin
+    #[arg(long, default_value_t = String::from(DEFAULT_CONFIG_FILENAME))]
+    pub config: String,
+
+    /// the endpoint resolved from the configuration file and the command line
+    #[arg(skip)]
+    pub endpoint: GameEndpoint,
+
     /// specify the current directory where the file will be added/written
     /// if --words-directory AND/OR --word-history specified, files with the same name will be created the working folder
     #[arg(long, default_value_t = String::from("./"))]
@@ -87,11 +191,57 @@ pub struct Cli {
     pub log: Option<String>,
 }
 
+/// files of the working directory, the ones of the game being used when they are not given
+#[derive(Clone, PartialEq, Eq, Debug, Args)]
+struct DataFiles {
+    /// the directory that contains all the words found (nearby)
+    /// words_folder/ by default, cemantle_folder/ with --game cemantle
+    #[arg(long)]
+    words_directory: Option<String>,
+
+    /// declare the name of the file in which the history of words found will be written
+    /// words_history by default, cemantle_history with --game cemantle
+    #[arg(long)]
+    word_history: Option<String>,
+
+    /// the directory that contains the record of each resolution
+    /// sessions/ by default, cemantle_sessions/ with --game cemantle
+    #[arg(long)]
+    sessions_directory: Option<String>,
+
+    /// the cache of the index of the words directory used by lookup and map
+    /// words_index by default, cemantle_index with --game cemantle
+    #[arg(long)]
+    words_index: Option<String>,
+
+    /// the file of word vectors built by the embeddings command
+    /// embeddings.bin by default, cemantle_embeddings.bin with --game cemantle
+    #[arg(long)]
+    embeddings: Option<String>,
+
+    /// the model turning the similarities of the embeddings into scores, written by the calibrate command
+    /// score_model.json by default, cemantle_score_model.json with --game cemantle
+    #[arg(long)]
+    score_model: Option<String>,
+}
+
 impl Cli {
-    fn init(&mut self) {
+    fn init(&mut self) -> Result<()> {
         let current = PathBuf::from(&self.working_directory);
-        self.word_history = current.join(&self.word_history).display().to_string();
-        self.words_directory = current.join(&self.words_directory).display().to_string();
+        let resolve = |file: &Option<String>, default: &str| {
+            current
+                .join(file.as_deref().unwrap_or(default))
+                .display()
+                .to_string()
+        };
+        self.word_history = resolve(&self.files.word_history, self.game.history_filename());
+        self.words_directory = resolve(&self.files.words_directory, self.game.words_folder());
+        self.sessions_directory =
+            resolve(&self.files.sessions_directory, self.game.sessions_folder());
+        self.words_index = resolve(&self.files.words_index, self.game.words_index());
+        self.embeddings = resolve(&self.files.embeddings, self.game.embeddings());
+        self.score_model = resolve(&self.files.score_model, self.game.score_model());
+        self.config = current.join(&self.config).display().to_string();
         if self.log.is_some() {
             let _ = self.log.insert(
                 current
@@ -100,20 +250,37 @@ impl Cli {
                     .to_string(),
             );
         }
+
+        self.endpoint = self.game_endpoint(self.game)?;
+        Ok(())
+    }
+
+    /// returns the endpoint of a game from the configuration file and the command line
+    pub fn game_endpoint(&self, game: Game) -> Result<GameEndpoint> {
+        let mut endpoint = Config::load(&self.config)?.endpoint(game);
+        if let Some(base_url) = self.base_url.as_ref() {
+            endpoint.base_url = base_url.to_owned();
+        }
+        if let Some(origin) = self.origin.as_ref() {
+            endpoint.origin = Some(origin.to_owned());
+        }
+        Ok(endpoint)
     }
     pub async fn matching(&mut self) -> Result<()> {
-        self.init();
+        self.init()?;
         let start = Local::now();
 
         match &self.command {
             Commands::Solve(solve) => {
+                let source_filename =
+                    self.words_list(solve.source_filename.as_deref(), self.game.words_list())?;
                 solve
-                    .solve_cemantix(&solve.source_filename, solve.batch_size, &self)
+                    .solve_cemantix(&source_filename, solve.batch_size, &self)
                     .await
             }
             Commands::Ruw(ruw) => {
                 let mut ruw = ruw.clone();
-                ruw.remove_useless_words(self.verbose).await
+                ruw.remove_useless_words(&self.endpoint, self.verbose).await
             }
             Commands::Nearby(nearby) => {
                 nearby
@@ -123,7 +290,25 @@ impl Cli {
 
             Commands::Extend(extend) => extend.extend_file(&self.words_directory).await,
             Commands::Sort(sort) => sort.sort_file(self).await,
-            Commands::Graph(graph) => graph.generate_graph(self, None).await,
+            Commands::Graph(graph) => graph.run(self).await,
+            Commands::Check(check) => check.check_data(self).await,
+            Commands::Export(export) => export.export_data(self).await,
+            Commands::Import(import) => import.import_data(self).await,
+            Commands::Merge(merge) => merge.merge_data(self).await,
+            Commands::Path(path) => path.find_path(self).await,
+            Commands::OpeningBook(opening_book) => opening_book.generate_opening_book(self).await,
+            Commands::Clusters(clusters) => clusters.print_clusters(self).await,
+            Commands::Lookup(lookup) => lookup.lookup_words(self).await,
+            Commands::Map(map) => map.generate_map(self).await,
+            Commands::Report(report) => report.generate_report(self).await,
+            Commands::Analyze(analyze) => analyze.analyze(self).await,
+            Commands::Diff(diff) => diff.diff_words(self).await,
+            Commands::Embeddings(embeddings) => embeddings.run(self).await,
+            Commands::Neighbors(neighbors) => neighbors.print_neighbors(self).await,
+            Commands::Calibrate(calibrate) => calibrate.calibrate(self).await,
+            Commands::Play(play) => play.play(self).await,
+            Commands::Serve(serve) => serve.serve(self).await,
+            Commands::Pedantix(pedantix) => pedantix.solve_pedantix(self).await,
         }?;
         self.log_and_print(
             &format!("Command {} executed successfully !", self.command),
@@ -142,6 +327,21 @@ impl Cli {
         Ok(())
     }
 
+    /// returns the list of words given on the command line, or the default one of the working directory
+    pub fn words_list(&self, list: Option<&str>, default: Option<&str>) -> Result<String> {
+        match (list, default) {
+            (Some(list), _) => Ok(list.to_owned()),
+            (None, Some(default)) => Ok(PathBuf::from(&self.working_directory)
+                .join(default)
+                .display()
+                .to_string()),
+            (None, None) => Err(anyhow::anyhow!(
+                "No words list is shipped for {:?}, give one as source file",
+                self.game
+            )),
+        }
+    }
+
     pub fn verify(&self) -> Result<()> {
         if let Some(log) = self.log.as_ref() {
             if !PathBuf::from(log).try_exists()? {
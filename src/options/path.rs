@@ -0,0 +1,45 @@
+use anyhow::Result;
+use clap::Args;
+
+use crate::semantic_graph::SemanticGraph;
+
+use super::options::{Cli, LogLevel};
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Path {
+    /// First word of the chain
+    pub from: String,
+
+    /// Last word of the chain
+    pub to: String,
+
+    /// Keep only the closest words of each found word
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+}
+
+impl Path {
+    pub async fn find_path(&self, cli: &Cli) -> Result<()> {
+        let graph = SemanticGraph::from_words_directory(&cli.words_directory, self.limit, cli)?;
+        let index = |word: &str| {
+            graph
+                .index(word)
+                .ok_or_else(|| anyhow::anyhow!("{word} is not in the collected data"))
+        };
+        let path = graph
+            .shortest_path(index(&self.from)?, index(&self.to)?)
+            .ok_or_else(|| anyhow::anyhow!("No path between {} and {}", self.from, self.to))?;
+
+        for hop in path.windows(2) {
+            println!(
+                "{} -> {} (score {})",
+                graph.nodes[hop[0].0], graph.nodes[hop[1].0], hop[1].1
+            );
+        }
+        cli.log_and_print(
+            &format!("{} hops from {} to {}", path.len() - 1, self.from, self.to),
+            LogLevel::Info,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use std::{collections::HashSet, fs::read_to_string, path::PathBuf};
+
+use anyhow::Result;
+use clap::Args;
+use futures::future::join_all;
+
+use crate::{
+    config::Game,
+    day::puzzle_date,
+    pedantix::{Article, PedantixResponse},
+    utils::{adding_word_to_historic, send_pedantix_request},
+    words_getter::WordGetter,
+};
+
+use super::options::{Cli, LogLevel};
+
+pub const DEFAULT_PEDANTIX_HISTORY_FILENAME: &str = "pedantix_history";
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Pedantix {
+    /// Source file of the words revealing the article, the most common French words otherwise
+    pub source_filename: Option<String>,
+
+    /// Number of words in batches not over 200
+    #[arg(short, long, default_value_t = 100)]
+    pub batch_size: usize,
+
+    /// File of the history of the pages found, inside the working directory
+    #[arg(long, default_value_t = String::from(DEFAULT_PEDANTIX_HISTORY_FILENAME))]
+    pub history: String,
+}
+
+impl Pedantix {
+    pub async fn solve_pedantix(&self, cli: &Cli) -> Result<()> {
+        let history = PathBuf::from(&cli.working_directory)
+            .join(&self.history)
+            .display()
+            .to_string();
+        if let Some(last) = WordGetter::get_last_found_word(&history)? {
+            if last.1 == puzzle_date() {
+                cli.log_and_print(
+                    &format!("Page already found ({}) !", last.0),
+                    LogLevel::Warn,
+                )?;
+                return Ok(());
+            }
+        }
+
+        // Pedantix has its own endpoint and list, whatever the game
+        let endpoint = cli.game_endpoint(Game::Pedantix)?;
+        let source_filename = cli.words_list(
+            self.source_filename.as_deref(),
+            Game::Pedantix.common_words_list(),
+        )?;
+        let words = read_to_string(&source_filename)
+            .map_err(|e| anyhow::anyhow!("Cannot read {source_filename} : {e}"))?
+            .lines()
+            .map(|w| w.trim().to_owned())
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<String>>();
+        let mut article = Article::default();
+        let mut tested = HashSet::new();
+        let mut won = false;
+        for batch in words.chunks(self.batch_size.clamp(1, 200)) {
+            let batch = batch
+                .iter()
+                .filter(|w| tested.insert(w.to_lowercase()))
+                .collect::<Vec<&String>>();
+            let responses =
+                join_all(batch.iter().map(|w| send_pedantix_request(&endpoint, w))).await;
+            for (word, response) in batch.iter().zip(responses) {
+                match response.and_then(|body| PedantixResponse::parse(word, &body)) {
+                    Ok(response) => {
+                        let revealed = article.update(&response);
+                        if cli.verbose && revealed > 0 {
+                            println!("{word} revealed {revealed} words");
+                        }
+                        won |= response.win;
+                    }
+                    Err(e) if cli.verbose => eprintln!("{word} : {e}"),
+                    Err(_) => {}
+                }
+            }
+            if won {
+                break;
+            }
+        }
+
+        println!("{}", article.render());
+        let candidates = article.title_candidates();
+        cli.log_and_print(
+            &format!(
+                "{} words revealed with {} words tested",
+                article.revealed(),
+                tested.len()
+            ),
+            LogLevel::Info,
+        )?;
+        // only a fully revealed title is recorded, the candidates may have gaps
+        match (won, article.title()) {
+            (true, Some(title)) => {
+                cli.log_and_print(&format!("Page found : {title}"), LogLevel::Info)?;
+                adding_word_to_historic(&title, &history, Game::Pedantix, cli).await?;
+            }
+            (true, None) => cli.log_and_print(
+                &format!(
+                    "Page found but its title is not fully revealed, not recorded, candidates : {}",
+                    candidates.join(" | ")
+                ),
+                LogLevel::Warn,
+            )?,
+            (false, _) => cli.log_and_print(
+                &format!(
+                    "Page not found, title candidates : {}",
+                    candidates.join(" | ")
+                ),
+                LogLevel::Warn,
+            )?,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    collections::HashSet,
+    io::{stdin, stdout, BufRead, Write},
+};
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::{
+    calibration::Calibration,
+    cemantix_word::{CemantixWord, MAX_RANK},
+    embeddings::{loader::read_vocabulary, scorer::OfflineScorer},
+    puzzle::Puzzle,
+    words_getter::WordGetter,
+};
+
+use super::options::{Cli, LogLevel};
+
+const GIVE_UP: &str = "/abandon";
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Play {
+    /// Replay a found word, or the word found at a date (dd-mm-yyyy), a random word otherwise
+    #[arg(short, long)]
+    pub day: Option<String>,
+
+    /// Pick the random word in this list, a word at each line
+    #[arg(long)]
+    pub vocabulary: Option<String>,
+
+    /// Number of guesses shown in the table
+    #[arg(short, long, default_value_t = 20)]
+    pub lines: usize,
+}
+
+/// guess of the player, numbered in the order of the guesses
+struct Entry {
+    number: usize,
+    word: CemantixWord,
+}
+
+impl Play {
+    fn emoji(word: &CemantixWord) -> &'static str {
+        match word.rank {
+            MAX_RANK => "🥳",
+            999 => "😱",
+            990.. => "🔥",
+            900.. => "🥵",
+            1.. => "😎",
+            _ if word.score < 0.0 => "🧊",
+            _ => "🥶",
+        }
+    }
+
+    fn format_entry(entry: &Entry) -> String {
+        let rank = if entry.word.rank > 0 {
+            format!(
+                "{:>4}‰ {}",
+                entry.word.rank,
+                "█".repeat(entry.word.rank as usize / 50)
+            )
+        } else {
+            String::new()
+        };
+        format!(
+            "{:>5}  {:<20} {:>7.2}°C {} {rank}",
+            entry.number,
+            entry.word.word,
+            Calibration::temperature(entry.word.score),
+            Self::emoji(&entry.word)
+        )
+    }
+
+    fn print_table(&self, entries: &[Entry], last: &Entry) {
+        println!("{}", Self::format_entry(last));
+        println!("{}", "-".repeat(60));
+        for entry in entries.iter().take(self.lines) {
+            println!("{}", Self::format_entry(entry));
+        }
+    }
+
+    /// replays a found word, or the word found at a date, picks a random word otherwise
+    pub fn open_puzzle(day: Option<&str>, vocabulary: Option<&str>, cli: &Cli) -> Result<Puzzle> {
+        let scorer = OfflineScorer::open(&cli.embeddings, &cli.score_model);
+        let Some(day) = day else {
+            let vocabulary = vocabulary.map(read_vocabulary).transpose()?;
+            return Puzzle::random(scorer?, vocabulary.as_ref());
+        };
+        let secret = WordGetter::resolve_day(day, &cli.word_history)?;
+        if let Err(e) = scorer.as_ref() {
+            cli.log_and_print(
+                &format!("Only the closest words of {secret} are known : {e}"),
+                LogLevel::Warn,
+            )?;
+        }
+        Puzzle::from_found_word(&secret, &cli.words_directory, scorer.ok())
+    }
+
+    pub async fn play(&self, cli: &Cli) -> Result<()> {
+        let puzzle = Self::open_puzzle(self.day.as_deref(), self.vocabulary.as_deref(), cli)?;
+        println!("Guess the secret word, {GIVE_UP} to give up");
+
+        let mut entries: Vec<Entry> = Vec::new();
+        let mut guessed = HashSet::new();
+        let mut input = stdin().lock();
+        loop {
+            print!("> ");
+            stdout().flush()?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                break;
+            }
+            let word = line.trim().to_lowercase();
+            if word.is_empty() {
+                continue;
+            }
+            if word == GIVE_UP {
+                println!("The word was {}", puzzle.secret);
+                let closest = puzzle
+                    .closest()
+                    .iter()
+                    .skip(1)
+                    .take(10)
+                    .map(|w| w.word.to_owned())
+                    .collect::<Vec<String>>();
+                println!("Closest words : {}", closest.join(", "));
+                break;
+            }
+            if !guessed.insert(word.to_owned()) {
+                println!("{word} has already been guessed");
+                continue;
+            }
+            let Some(score) = puzzle.score(&word) else {
+                println!("I don't know the word {word}");
+                guessed.remove(&word);
+                continue;
+            };
+            let entry = Entry {
+                number: entries.len() + 1,
+                word: score,
+            };
+            let found = entry.word.word == puzzle.secret;
+            self.print_table(&entries, &entry);
+            let position = entries.partition_point(|e| e.word.score >= entry.word.score);
+            entries.insert(position, entry);
+            if found {
+                println!(
+                    "Well done, you found {} in {} guesses !",
+                    puzzle.secret,
+                    entries.len()
+                );
+                break;
+            }
+        }
+        Ok(())
+    }
+}
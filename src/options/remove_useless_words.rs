@@ -10,7 +10,7 @@ use std::{
 use clap::Args;
 use futures::{future::join_all, lock::Mutex};
 
-use crate::utils::send_request;
+use crate::{config::GameEndpoint, utils::send_request};
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
 pub struct Ruw {
@@ -28,7 +28,11 @@ pub struct Ruw {
     pub batch_size: usize,
 }
 impl Ruw {
-    pub async fn remove_useless_words(&mut self, verbose: bool) -> anyhow::Result<()> {
+    pub async fn remove_useless_words(
+        &mut self,
+        endpoint: &GameEndpoint,
+        verbose: bool,
+    ) -> anyhow::Result<()> {
         if self.batch_size > 200 {
             println!("Set number of threads to 200");
             self.batch_size = 200;
@@ -72,14 +76,20 @@ impl Ruw {
             words_list[_index % self.batch_size] = word.to_owned();
             let file_copy = Arc::clone(&sorted_file);
             let n = Arc::clone(&nb);
-            self.launch_threads_ruw(words_list.clone(), file_copy, n, verbose)
-                .await;
+            self.launch_threads_ruw(endpoint, words_list.clone(), file_copy, n, verbose)
+                .await?;
         }
 
         // there words left
         if total % self.batch_size != 0 {
-            self.launch_threads_ruw(words_list, sorted_file.clone(), nb.clone(), verbose)
-                .await;
+            self.launch_threads_ruw(
+                endpoint,
+                words_list,
+                sorted_file.clone(),
+                nb.clone(),
+                verbose,
+            )
+            .await?;
         }
 
         println!("{:?} mots gardés sur {total} mots", nb);
@@ -89,20 +99,28 @@ impl Ruw {
 
     pub async fn launch_threads_ruw(
         &self,
+        endpoint: &GameEndpoint,
         words_vec: Vec<String>,
         file: Arc<Mutex<fs::File>>,
         nb: Arc<AtomicUsize>,
         verbose: bool,
-    ) {
+    ) -> anyhow::Result<()> {
         let mut futures = Vec::new();
 
         for word in words_vec.iter() {
-            futures.push(send_request(&word));
+            futures.push(send_request(endpoint, &word));
         }
 
         let mut words_to_write: Vec<String> = Vec::new();
 
         let all_res = join_all(futures).await;
+        // the words are kept only if the game has been reached
+        if let Some(Err(e)) = all_res
+            .iter()
+            .find(|r| matches!(r, Err(e) if e.to_string().starts_with("cannot reach")))
+        {
+            return Err(anyhow::anyhow!("{e}"));
+        }
         for i in 0..self.batch_size {
             match all_res.get(i) {
                 Some(v) => match v {
@@ -136,5 +154,6 @@ impl Ruw {
             f.write_all(&data).unwrap();
             drop(f);
         }
+        Ok(())
     }
 }
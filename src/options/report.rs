@@ -0,0 +1,184 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use clap::Args;
+
+use crate::{
+    calibration::Calibration, cemantix_word::CemantixWord, day::puzzle_number, session::Session,
+    stats::histogram, words_getter::WordGetter,
+};
+
+use super::options::{Cli, LogLevel};
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em auto;max-width:60em;color:#222}\
+table{border-collapse:collapse}td,th{padding:.2em .8em;border-bottom:1px solid #ddd;text-align:right}\
+td:first-child,th:first-child{text-align:left}.bar{fill:#1f77b4}";
+const HISTOGRAM_BINS: usize = 20;
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Report {
+    /// Destination directory of the pages
+    #[arg(short, long, default_value_t = String::from("report/"))]
+    pub output: String,
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// link to the page of a word, percent-encoded as the words may contain accents, spaces or #
+pub fn href(word: &str) -> String {
+    format!("{word}.html")
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"fr\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape(title)
+    )
+}
+
+impl Report {
+    /// inline SVG of the distribution of the scores
+    fn histogram_svg(words: &[CemantixWord]) -> String {
+        let scores = words.iter().map(|w| w.score).collect::<Vec<f32>>();
+        let min = scores.iter().copied().fold(0.0_f32, f32::min);
+        let counts = histogram(&scores, HISTOGRAM_BINS, min, 1.0);
+        let highest = counts.iter().copied().max().unwrap_or(1).max(1);
+        let (width, height) = (600.0, 200.0);
+        let bar = width / HISTOGRAM_BINS as f32;
+        let mut svg = format!("<svg width=\"{width}\" height=\"{}\">\n", height + 20.0);
+        for (i, count) in counts.iter().enumerate() {
+            let h = *count as f32 / highest as f32 * height;
+            svg += &format!(
+                "<rect class=\"bar\" x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{h:.1}\"><title>{count}</title></rect>\n",
+                i as f32 * bar,
+                height - h,
+                bar - 1.0
+            );
+        }
+        svg += &format!(
+            "<text x=\"0\" y=\"{}\" font-size=\"12\">{min:.2}</text><text x=\"{}\" y=\"{}\" font-size=\"12\" text-anchor=\"end\">1.00</text>\n</svg>\n",
+            height + 15.0,
+            width,
+            height + 15.0
+        );
+        svg
+    }
+
+    fn day_page(word: &str, date: &NaiveDate, cli: &Cli) -> Result<String> {
+        let mut words = WordGetter::get_cemantix_words_of_found_word(word, &cli.words_directory)?;
+        words.sort();
+        words.reverse();
+
+        let mut body = format!(
+            "<p><a href=\"index.html\">Index</a></p>\n<h1>{}</h1>\n<p>{} (#{})</p>\n",
+            escape(word),
+            date.format(crate::HISTORY_FORMAT),
+            puzzle_number(cli.game, *date)
+        );
+
+        match Session::load(word, &cli.sessions_directory) {
+            Ok(session) => {
+                body += &format!(
+                    "<h2>Session</h2>\n<p>{} requests in {:.1}s</p>\n<table>\n<tr><th>word</th><th>score</th><th>time</th></tr>\n",
+                    session.requests,
+                    session.duration_ms as f32 / 1000.0
+                );
+                for guess in session.timeline.iter() {
+                    body += &format!(
+                        "<tr><td>{}</td><td>{:.4}</td><td>{:.1}s</td></tr>\n",
+                        escape(&guess.word),
+                        guess.score,
+                        guess.elapsed_ms as f32 / 1000.0
+                    );
+                }
+                body += "</table>\n";
+            }
+            Err(_) => body += "<h2>Session</h2>\n<p>No session recorded</p>\n",
+        }
+
+        body += "<h2>Distribution of the scores</h2>\n";
+        body += &Self::histogram_svg(&words);
+
+        body += &format!(
+            "<h2>Closest words ({})</h2>\n<table>\n<tr><th>word</th><th>rank</th><th>score</th><th>°C</th></tr>\n",
+            words.len()
+        );
+        for w in words.iter() {
+            body += &format!(
+                "<tr><td>{}</td><td>{}</td><td>{:.4}</td><td>{:.2}</td></tr>\n",
+                escape(&w.word),
+                if w.rank > 0 {
+                    w.rank.to_string()
+                } else {
+                    String::new()
+                },
+                w.score,
+                Calibration::temperature(w.score)
+            );
+        }
+        body += "</table>\n";
+        Ok(page(word, &body))
+    }
+
+    pub async fn generate_report(&self, cli: &Cli) -> Result<()> {
+        fs::create_dir_all(&self.output)?;
+        let mut history = WordGetter::get_history(&cli.word_history)?;
+        history.sort_by_key(|(_, date)| std::cmp::Reverse(*date));
+
+        let mut index =
+            String::from("<h1>Cemantix</h1>\n<table>\n<tr><th>word</th><th>date</th></tr>\n");
+        let mut generated = 0;
+        for (word, date) in history.iter() {
+            match Self::day_page(word, date, cli) {
+                Ok(html) => {
+                    fs::write(
+                        PathBuf::from(&self.output).join(format!("{word}.html")),
+                        html,
+                    )?;
+                    index += &format!(
+                        "<tr><td><a href=\"{}\">{}</a></td><td>{}</td></tr>\n",
+                        href(word),
+                        escape(word),
+                        date.format(crate::HISTORY_FORMAT)
+                    );
+                    generated += 1;
+                }
+                Err(e) => {
+                    cli.log_and_print(
+                        &format!("Cannot generate the page of {word} : {e}"),
+                        LogLevel::Warn,
+                    )?;
+                    index += &format!(
+                        "<tr><td>{}</td><td>{}</td></tr>\n",
+                        escape(word),
+                        date.format(crate::HISTORY_FORMAT)
+                    );
+                }
+            }
+        }
+        index += "</table>\n";
+        fs::write(
+            PathBuf::from(&self.output).join("index.html"),
+            page("Cemantix", &index),
+        )?;
+        cli.log_and_print(
+            &format!("{generated} pages written into {}", self.output),
+            LogLevel::Info,
+        )?;
+        Ok(())
+    }
+}
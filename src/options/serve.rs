@@ -0,0 +1,227 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use clap::Args;
+use serde_json::json;
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+
+use crate::{puzzle::Puzzle, words_getter::WordGetter};
+
+use super::{
+    import::Import,
+    options::{Cli, LogLevel},
+    play::Play,
+};
+
+/// largest body read from a request, the game only sends a word
+pub const MAX_BODY_SIZE: usize = 4096;
+/// longest request or header line
+pub const MAX_LINE_SIZE: usize = 8192;
+/// time given to a client to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
+pub struct Serve {
+    /// Address the server listens on
+    #[arg(short, long, default_value_t = String::from("127.0.0.1:8080"))]
+    pub address: String,
+
+    /// Serve a found word, or the word found at a date (dd-mm-yyyy), a random word otherwise
+    #[arg(short, long)]
+    pub day: Option<String>,
+
+    /// Pick the random word in this list, a word at each line
+    #[arg(long)]
+    pub vocabulary: Option<String>,
+}
+
+/// request received by the server, only the parts the game uses
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub form: HashMap<String, String>,
+    /// length of the body announced by the client, the body is not read above `MAX_BODY_SIZE`
+    pub length: usize,
+}
+
+impl Serve {
+    /// reads a line of at most `MAX_LINE_SIZE` bytes, returns its size
+    async fn read_line(
+        reader: &mut (impl AsyncBufRead + Unpin),
+        line: &mut String,
+    ) -> Result<usize> {
+        line.clear();
+        let size = reader.take(MAX_LINE_SIZE as u64).read_line(line).await?;
+        if size == MAX_LINE_SIZE && !line.ends_with('\n') {
+            return Err(anyhow::anyhow!("Line longer than {MAX_LINE_SIZE} bytes"));
+        }
+        Ok(size)
+    }
+
+    pub async fn read_request(stream: impl AsyncRead + Unpin) -> Result<Request> {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        Self::read_line(&mut reader, &mut line).await?;
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            return Err(anyhow::anyhow!("Invalid request line : {line}"));
+        };
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let (method, path, query) = (method.to_owned(), path.to_owned(), query.to_owned());
+
+        let mut length = 0;
+        loop {
+            if Self::read_line(&mut reader, &mut line).await? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    length = value.trim().parse().map_err(|_| {
+                        anyhow::anyhow!("Invalid Content-Length : {}", value.trim())
+                    })?;
+                }
+            }
+        }
+        let mut body = Vec::new();
+        if length <= MAX_BODY_SIZE {
+            body.resize(length, 0);
+            reader.read_exact(&mut body).await?;
+        }
+
+        let form = form_urlencoded::parse(query.as_bytes())
+            .chain(form_urlencoded::parse(&body))
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        Ok(Request {
+            method,
+            path,
+            form,
+            length,
+        })
+    }
+
+    /// answers like the game : the score of a word, or the closest words of a found word
+    pub fn respond(request: &Request, puzzle: &Puzzle, words_directory: &str) -> (u16, String) {
+        if request.method != "POST" && request.method != "GET" {
+            return (405, json!({"error": "Method not allowed"}).to_string());
+        }
+        if request.length > MAX_BODY_SIZE {
+            return (413, json!({"error": "Payload too large"}).to_string());
+        }
+        let word = request
+            .form
+            .get("word")
+            .map(|w| w.trim().to_lowercase())
+            .unwrap_or_default();
+        match request.path.as_str() {
+            "/score" => match puzzle.score(&word) {
+                Some(w) if w.rank > 0 => (
+                    200,
+                    json!({"score": w.score, "percentile": w.rank}).to_string(),
+                ),
+                Some(w) => (200, json!({"score": w.score}).to_string()),
+                None => (
+                    200,
+                    json!({"error": format!("Je ne connais pas le mot <i>{word}</i>.")})
+                        .to_string(),
+                ),
+            },
+            "/nearby" => {
+                let words = if word == puzzle.secret {
+                    let mut closest = puzzle
+                        .closest()
+                        .into_iter()
+                        .filter(|w| w.rank > 0 && w.word != puzzle.secret)
+                        .map(|w| (w.word.to_owned(), w.rank, w.score))
+                        .collect::<Vec<(String, isize, f32)>>();
+                    closest.sort_by_key(|w| w.1);
+                    Some(closest)
+                } else if Import::is_valid_name(&word) {
+                    WordGetter::get_cemantix_words_of_found_word(&word, words_directory)
+                        .ok()
+                        .map(|words| {
+                            let mut words = words
+                                .into_iter()
+                                .filter(|w| w.rank > 0)
+                                .map(|w| (w.word, w.rank, w.score))
+                                .collect::<Vec<(String, isize, f32)>>();
+                            words.sort_by_key(|w| w.1);
+                            words
+                        })
+                } else {
+                    None
+                };
+                match words {
+                    Some(words) => (200, json!(words).to_string()),
+                    None => (
+                        200,
+                        json!({"error": format!("Le mot {word} n'a pas encore été trouvé.")})
+                            .to_string(),
+                    ),
+                }
+            }
+            _ => (404, json!({"error": "Not found"}).to_string()),
+        }
+    }
+
+    async fn handle(
+        mut stream: TcpStream,
+        puzzle: Arc<Puzzle>,
+        words_directory: Arc<String>,
+    ) -> Result<()> {
+        let (status, body) = match timeout(READ_TIMEOUT, Self::read_request(&mut stream)).await {
+            Ok(Ok(request)) => Self::respond(&request, &puzzle, &words_directory),
+            Ok(Err(e)) => (400, json!({"error": e.to_string()}).to_string()),
+            Err(_) => (408, json!({"error": "Request timeout"}).to_string()),
+        };
+        let reason = match status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            413 => "Payload Too Large",
+            _ => "Internal Server Error",
+        };
+        let response = format!(
+            "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nAccess-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+
+    pub async fn serve(&self, cli: &Cli) -> Result<()> {
+        let puzzle = Arc::new(Play::open_puzzle(
+            self.day.as_deref(),
+            self.vocabulary.as_deref(),
+            cli,
+        )?);
+        let words_directory = Arc::new(cli.words_directory.to_owned());
+        let listener = TcpListener::bind(&self.address).await?;
+        cli.log_and_print(
+            &format!(
+                "Serving on http://{}, use --base-url http://{} to play against it",
+                self.address, self.address
+            ),
+            LogLevel::Info,
+        )?;
+        if cli.verbose {
+            println!("The word is {}", puzzle.secret);
+        }
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let (puzzle, words_directory) = (puzzle.clone(), words_directory.clone());
+            tokio::spawn(async move {
+                if let Err(e) = Self::handle(stream, puzzle, words_directory).await {
+                    eprintln!("Request failed : {e}");
+                }
+            });
+        }
+    }
+}
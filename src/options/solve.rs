@@ -7,19 +7,33 @@ use futures::future::join_all;
 use futures::lock::Mutex;
 use std::collections::HashSet;
 use std::io::BufRead;
-use std::{fs::OpenOptions, io::BufReader, sync::Arc};
+use std::{
+    fs::{self, OpenOptions},
+    io::BufReader,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
 
 use crate::utils::{adding_word_to_historic, send_request, send_words};
-use crate::{cemantix_word::CemantixWord, words_getter::WordGetter};
+use crate::{
+    calibration::Calibration,
+    cemantix_word::CemantixWord,
+    config::GameEndpoint,
+    day::puzzle_date,
+    embeddings::cache::EmbeddingsCache,
+    session::{Guess, Session},
+    words_getter::WordGetter,
+};
 
 use super::graph::Graph;
 use super::nearby::Nearby;
+use super::neighbors::Neighbors;
 use super::options::Cli;
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Args)]
 pub struct Solve {
-    // Source file of the words to brute force
-    pub source_filename: String,
+    // Source file of the words to brute force, the words list of the game otherwise
+    pub source_filename: Option<String>,
 
     /// Line index from which solving starts
     #[arg(short, long, default_value_t = 0)]
@@ -32,6 +46,10 @@ pub struct Solve {
     /// fetch data for graph generation
     #[arg(short, long, default_value_t = false)]
     pub graph: bool,
+
+    /// File of words in the working directory tested before the source file (see opening-book)
+    #[arg(short, long)]
+    pub opening_book: Option<String>,
 }
 
 #[derive(Debug)]
@@ -41,6 +59,7 @@ pub struct DataThread {
     filename: String,
     pub words_data: HashSet<CemantixWord>,
     pub nb_tested_words: usize,
+    pub timeline: Vec<Guess>,
 }
 
 impl DataThread {
@@ -57,14 +76,16 @@ impl DataThread {
             score,
             words_data,
             nb_tested_words,
+            timeline: Vec::new(),
         }
     }
 
     pub fn save_into_file(&mut self, cli: &Cli) -> Result<()> {
-        let file = WordGetter::get_file_word(&self.word, false, true, false, &cli.words_directory)?;
-        let mut copy = Vec::from_iter(self.words_data.iter());
-        copy.sort();
-        Ok(serde_json::to_writer(file, &copy)?)
+        WordGetter::save_cemantix_words_of_found_word(
+            &self.word,
+            &self.words_data,
+            &cli.words_directory,
+        )
     }
 }
 
@@ -76,6 +97,7 @@ impl Default for DataThread {
             filename: String::new(),
             words_data: HashSet::new(),
             nb_tested_words: 0,
+            timeline: Vec::new(),
         }
     }
 }
@@ -84,7 +106,7 @@ impl Solve {
     pub async fn solve_cemantix(&self, filename: &str, batch_size: usize, cli: &Cli) -> Result<()> {
         let last_word = WordGetter::get_last_found_word(&cli.word_history)?;
         if let Some(last) = last_word {
-            if last.1 == Local::now().date_naive() {
+            if last.1 == puzzle_date() {
                 cli.log_and_print(
                     &format!("Word already found ({}) !", last.0),
                     LogLevel::Warn,
@@ -95,44 +117,121 @@ impl Solve {
 
         let reader = BufReader::new(OpenOptions::new().read(true).open(filename)?);
         let reader2 = BufReader::new(OpenOptions::new().read(true).open(filename)?);
+        let opening_book: Vec<String> = match self.opening_book.as_ref() {
+            // read only, a mistyped name must not create an empty book
+            Some(book) => fs::read_to_string(PathBuf::from(&cli.working_directory).join(book))
+                .map_err(|e| anyhow::anyhow!("Cannot read the opening book {book} : {e}"))?
+                .lines()
+                .map(str::to_owned)
+                .collect(),
+            None => Vec::new(),
+        };
+        // the words of the book are not tested again when the source file reaches them
+        let book_words = opening_book.iter().cloned().collect::<HashSet<String>>();
+        let source_count = reader
+            .lines()
+            .flatten()
+            .filter(|w| !book_words.contains(w))
+            .count();
+        let source_words = reader2
+            .lines()
+            .flatten()
+            .filter(move |w| !book_words.contains(w));
 
         let best_word = Arc::new(Mutex::new(DataThread::default()));
+        let start = Local::now();
+        // learnt once a first best word has to be displayed, reading every nearby file is slow
+        let calibration = OnceLock::new();
+        let calibration = &calibration;
         let callback_solver = |best_word: Arc<Mutex<DataThread>>,
                                data: Vec<(String, Option<f32>)>| async move {
             //TODO use new fucnton fromTuple
-            let winner = data
+            let mut data = data;
+            while let Some((word, value)) = data
                 .iter()
                 .filter(|v| v.1.is_some())
-                .max_by(|x, y| x.1.unwrap().total_cmp(y.1.as_ref().unwrap()));
-            if let Some(winner) = winner {
-                let value = winner.1.unwrap();
+                .max_by(|x, y| x.1.unwrap().total_cmp(y.1.as_ref().unwrap()))
+                .map(|v| (v.0.to_owned(), v.1.unwrap()))
+            {
                 let mut best_w = best_word.lock().await;
                 best_w.nb_tested_words += data.len();
+                if value > best_w.score {
+                    best_w.timeline.push(Guess {
+                        elapsed_ms: (Local::now() - start).num_milliseconds(),
+                        word: word.to_owned(),
+                        score: value,
+                    });
+                }
                 if value == 1.0 {
                     best_w.score = value;
-                    best_w.word = winner.0.to_owned();
-                    cli.log_and_print(&format!("word found : {} ", winner.0), LogLevel::Info)?;
+                    best_w.word = word.to_owned();
+                    cli.log_and_print(&format!("word found : {} ", word), LogLevel::Info)?;
                     return Ok(true);
-                } else {
-                    if value > best_w.score {
-                        best_w.score = value;
-                        best_w.word = winner.0.to_owned();
-                        println!("New best word : {} with a score of {}", winner.0, value);
+                }
+                // word whose closest words are tested before going on with the list
+                let mut explore = None;
+                if value > best_w.score {
+                    let calibration = calibration
+                        .get_or_init(|| {
+                            let history =
+                                WordGetter::get_history(&cli.word_history).unwrap_or_default();
+                            Calibration::from_words_directory(
+                                &cli.words_directory,
+                                &history,
+                                puzzle_date(),
+                            )
+                            .ok()
+                        })
+                        .as_ref();
+                    let temperature = Calibration::temperature(value);
+                    match calibration.and_then(|c| c.rank(value)) {
+                        Some(rank) => println!(
+                            "New best word : {} with a score of {} ({temperature:.2}°C, ~{rank}‰)",
+                            word, value
+                        ),
+                        None => println!(
+                            "New best word : {} with a score of {} ({temperature:.2}°C)",
+                            word, value
+                        ),
+                    }
+                    if calibration.is_some_and(|c| {
+                        c.is_in_neighbourhood(value) && !c.is_in_neighbourhood(best_w.score)
+                    }) {
+                        cli.log_and_print(
+                            "In the neighbourhood of the word of the day",
+                            LogLevel::Info,
+                        )?;
+                        explore = Some(word.to_owned());
                     }
+                    best_w.score = value;
+                    best_w.word = word.to_owned();
                 }
                 drop(best_w);
+
+                // the closest words of a word of the neighbourhood are likely close to the answer
+                match explore.and_then(|w| Self::closest_words(&w, batch_size, cli)) {
+                    Some(words) if !words.is_empty() => {
+                        cli.log_and_print(
+                            &format!("Testing the {} closest words of {word}", words.len()),
+                            LogLevel::Info,
+                        )?;
+                        data = Self::launch_threads_solve(&cli.endpoint, words).await?;
+                    }
+                    _ => break,
+                }
             }
             Ok(false)
         };
         send_words(
-            reader.lines().flatten().count(),
-            reader2.lines().flatten(),
+            &cli.endpoint,
+            opening_book.len() + source_count,
+            opening_book.into_iter().chain(source_words),
             batch_size,
             best_word.clone(),
             callback_solver,
             cli.verbose,
         )
-        .await;
+        .await?;
 
         let b = best_word.lock().await;
         cli.log_and_print(
@@ -141,12 +240,27 @@ impl Solve {
         )?;
 
         // save new found word and new words related to found word
-        if let Err(e) = adding_word_to_historic(&b.word, &cli.word_history, cli).await {
+        if let Err(e) = adding_word_to_historic(&b.word, &cli.word_history, cli.game, cli).await {
             cli.log_and_print(
                 &format!("Cannot append {} to historical words : {e}", b.word),
                 LogLevel::Error,
             )?;
         }
+        if b.score == 1.0 {
+            let session = Session {
+                word: b.word.to_owned(),
+                date: puzzle_date().format(crate::HISTORY_FORMAT).to_string(),
+                duration_ms: (Local::now() - start).num_milliseconds(),
+                requests: b.nb_tested_words,
+                timeline: b.timeline.clone(),
+            };
+            if let Err(e) = session.save(&cli.sessions_directory) {
+                cli.log_and_print(
+                    &format!("Cannot save the session of {} : {e}", b.word),
+                    LogLevel::Error,
+                )?;
+            }
+        }
         if let Err(e) = Extend::new(b.filename.to_owned())
             .extend_file(&cli.words_directory)
             .await
@@ -174,17 +288,34 @@ impl Solve {
         }
         Ok(())
     }
-    pub async fn launch_threads_solve(words_batch: Vec<String>) -> Vec<(String, Option<f32>)> {
-        join_all(words_batch.iter().map(|word| send_request(&word)))
+    /// closest words of `word` in the embeddings, None if there are no embeddings or if it is not in them
+    fn closest_words(word: &str, k: usize, cli: &Cli) -> Option<Vec<String>> {
+        let cache = EmbeddingsCache::open(&cli.embeddings).ok()?;
+        let vector = cache.vector(cache.index(word)?);
+        let index = Neighbors::load_index(&cache, cli).ok()?;
+        Some(
+            Neighbors::similar_words(&cache, &index, &vector, k + 1, k.max(100))
+                .into_iter()
+                .map(|w| w.word)
+                .filter(|w| w != word)
+                .take(k)
+                .collect(),
+        )
+    }
+    pub async fn launch_threads_solve(
+        endpoint: &GameEndpoint,
+        words_batch: Vec<String>,
+    ) -> Result<Vec<(String, Option<f32>)>> {
+        join_all(words_batch.iter().map(|word| send_request(endpoint, &word)))
             .await
-            .iter()
-            .enumerate()
-            .map(|(i, v)| {
-                (
-                    words_batch.get(i).unwrap().to_owned(),
-                    v.as_ref().ok().cloned(),
-                )
+            .into_iter()
+            .zip(words_batch)
+            .map(|(v, word)| match v {
+                Ok(score) => Ok((word, Some(score))),
+                // the other errors only concern the word
+                Err(e) if e.to_string().starts_with("cannot reach") => Err(e),
+                Err(_) => Ok((word, None)),
             })
-            .collect::<Vec<(String, Option<f32>)>>()
+            .collect::<Result<Vec<(String, Option<f32>)>>>()
     }
 }
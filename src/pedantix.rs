@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde_json::Value;
+
+/// words ending the title in the first sentence of an article ("Paris est la capitale...")
+const TITLE_END_WORDS: [&str; 6] = ["est", "sont", "était", "étaient", "fut", "désigne"];
+/// positions in which the end of the title is looked for
+const TITLE_MAX_LENGTH: usize = 30;
+
+/// what is known about a position of the hidden article
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reveal {
+    /// the word at this position
+    Word(String),
+    /// closest guess tried at this position and its proximity
+    Close(String, f32),
+}
+
+/// answer of the game to a guess, parsed from
+/// `{"score": {"<position>": "<revealed word>" | <proximity>, ...}, "win": <bool>}`
+#[derive(Debug, Default)]
+pub struct PedantixResponse {
+    pub positions: Vec<(usize, Reveal)>,
+    pub win: bool,
+}
+
+impl PedantixResponse {
+    pub fn parse(guess: &str, body: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(body).map_err(|_| {
+            anyhow::anyhow!(
+                "unexpected response : {}",
+                body.chars().take(80).collect::<String>()
+            )
+        })?;
+        if let Some(error) = value.get("error") {
+            return Err(anyhow::anyhow!("server error : {error}"));
+        }
+
+        let mut response = Self {
+            positions: Vec::new(),
+            win: value.get("win").and_then(Value::as_bool).unwrap_or(false),
+        };
+        if let Some(score) = value.get("score").and_then(Value::as_object) {
+            for (position, reveal) in score.iter() {
+                let position = position.parse::<usize>()?;
+                let reveal = match reveal {
+                    Value::String(word) => Reveal::Word(word.to_owned()),
+                    Value::Number(proximity) => Reveal::Close(
+                        guess.to_owned(),
+                        proximity.as_f64().unwrap_or_default() as f32,
+                    ),
+                    _ => return Err(anyhow::anyhow!("invalid position {position} : {reveal}")),
+                };
+                response.positions.push((position, reveal));
+            }
+        }
+        Ok(response)
+    }
+}
+
+/// hidden article rebuilt from the answers of the game
+#[derive(Debug, Default)]
+pub struct Article {
+    pub positions: BTreeMap<usize, Reveal>,
+}
+
+impl Article {
+    /// merges an answer, a revealed word is never replaced and a closer guess replaces a farther one
+    /// returns the number of words revealed by the answer
+    pub fn update(&mut self, response: &PedantixResponse) -> usize {
+        let mut revealed = 0;
+        for (position, reveal) in response.positions.iter() {
+            let replace = match (self.positions.get(position), reveal) {
+                (Some(Reveal::Word(_)), _) => false,
+                (Some(Reveal::Close(_, known)), Reveal::Close(_, proximity)) => proximity > known,
+                _ => true,
+            };
+            if replace {
+                revealed += matches!(reveal, Reveal::Word(_)) as usize;
+                self.positions.insert(*position, reveal.clone());
+            }
+        }
+        revealed
+    }
+
+    pub fn revealed(&self) -> usize {
+        self.positions
+            .values()
+            .filter(|r| matches!(r, Reveal::Word(_)))
+            .count()
+    }
+
+    /// position of the first verb of the article, the title being the words before it
+    fn title_end(&self) -> Option<usize> {
+        (0..TITLE_MAX_LENGTH).find(|p| {
+            matches!(self.positions.get(p), Some(Reveal::Word(w)) if TITLE_END_WORDS.contains(&w.to_lowercase().as_str()))
+        })
+    }
+
+    /// title of the article if every word preceding its first verb is revealed
+    pub fn title(&self) -> Option<String> {
+        let end = self.title_end()?;
+        (0..end)
+            .map(|p| match self.positions.get(&p) {
+                Some(Reveal::Word(w)) => Some(w.as_str()),
+                _ => None,
+            })
+            .collect::<Option<Vec<&str>>>()
+            .filter(|words| !words.is_empty())
+            .map(|words| words.join(" "))
+    }
+
+    /// possible titles : the words preceding the first verb of the article, the gaps being
+    /// filled with the closest guesses, then with revealed words only
+    pub fn title_candidates(&self) -> Vec<String> {
+        let Some(end) = self.title_end() else {
+            return Vec::new();
+        };
+        let mut candidates = Vec::new();
+        let filled = (0..end)
+            .map(|p| match self.positions.get(&p) {
+                Some(Reveal::Word(w)) | Some(Reveal::Close(w, _)) => Some(w.as_str()),
+                None => None,
+            })
+            .collect::<Option<Vec<&str>>>();
+        if let Some(filled) = filled.filter(|f| !f.is_empty()) {
+            candidates.push(filled.join(" "));
+        }
+        let revealed = (0..end)
+            .filter_map(|p| match self.positions.get(&p) {
+                Some(Reveal::Word(w)) => Some(w.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<&str>>()
+            .join(" ");
+        if !revealed.is_empty() && !candidates.contains(&revealed) {
+            candidates.push(revealed);
+        }
+        candidates
+    }
+
+    /// the article with the hidden words as blocks and the close guesses between brackets
+    pub fn render(&self) -> String {
+        let Some(last) = self.positions.keys().last() else {
+            return String::new();
+        };
+        (0..=*last)
+            .map(|p| match self.positions.get(&p) {
+                Some(Reveal::Word(w)) => w.to_owned(),
+                Some(Reveal::Close(w, _)) => format!("[{w}]"),
+                None => String::from("█"),
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap, HashSet},
+    hash::{BuildHasher, Hasher},
+};
+
+use anyhow::Result;
+
+use crate::{
+    cemantix_word::{CemantixWord, MAX_RANK},
+    embeddings::scorer::OfflineScorer,
+    words_getter::WordGetter,
+};
+
+/// word of the day scored without the game, from its stored closest words or from the embeddings
+pub struct Puzzle {
+    pub secret: String,
+    /// closest words of the secret with their rank
+    closest: HashMap<String, CemantixWord>,
+    /// scores the words outside of the closest words
+    scorer: Option<OfflineScorer>,
+}
+
+impl Puzzle {
+    /// replays a found word from its file of the words directory
+    pub fn from_found_word(
+        secret: &str,
+        words_fcontainer_name: &str,
+        scorer: Option<OfflineScorer>,
+    ) -> Result<Self> {
+        let mut closest =
+            WordGetter::get_cemantix_words_of_found_word(secret, words_fcontainer_name)?
+                .into_iter()
+                .filter(|w| w.is_valid())
+                .map(|w| (w.word.to_owned(), w))
+                .collect::<HashMap<String, CemantixWord>>();
+        closest.insert(
+            secret.to_owned(),
+            CemantixWord::new(secret.to_owned(), MAX_RANK, 1.0),
+        );
+        Ok(Self {
+            secret: secret.to_owned(),
+            closest,
+            scorer,
+        })
+    }
+
+    /// ranks the closest words of the secret from the embeddings
+    pub fn from_embeddings(secret: &str, scorer: OfflineScorer) -> Result<Self> {
+        let Some(index) = scorer.cache.index(secret) else {
+            return Err(anyhow::anyhow!("{secret} is not in the embeddings"));
+        };
+        let vector = scorer.cache.vector(index);
+        let mut similarities = (0..scorer.cache.len())
+            .map(|i| (i, scorer.cache.similarity_to(&vector, i)))
+            .collect::<Vec<(usize, f32)>>();
+        // the secret comes first even if the quantisation made it slightly less similar to itself
+        similarities.sort_by(|a, b| {
+            (b.0 == index)
+                .cmp(&(a.0 == index))
+                .then(b.1.total_cmp(&a.1))
+        });
+        let closest = similarities
+            .into_iter()
+            .take(MAX_RANK as usize)
+            .enumerate()
+            .map(|(position, (i, similarity))| {
+                let word = scorer.cache.words[i].to_owned();
+                let score = if i == index {
+                    1.0
+                } else {
+                    scorer
+                        .model
+                        .as_ref()
+                        .map_or(similarity, |m| m.apply(similarity))
+                };
+                (
+                    word.to_owned(),
+                    CemantixWord::new(word, MAX_RANK - position as isize, score),
+                )
+            })
+            .collect();
+        Ok(Self {
+            secret: secret.to_owned(),
+            closest,
+            scorer: Some(scorer),
+        })
+    }
+
+    /// picks a random secret among the words of the embeddings, or of `vocabulary` if given
+    pub fn random(scorer: OfflineScorer, vocabulary: Option<&HashSet<String>>) -> Result<Self> {
+        let candidates = scorer
+            .cache
+            .words
+            .iter()
+            .filter(|w| vocabulary.is_none_or(|v| v.contains(*w)))
+            .collect::<Vec<&String>>();
+        if candidates.is_empty() {
+            return Err(anyhow::anyhow!("No word to pick the secret from"));
+        }
+        let random = RandomState::new().build_hasher().finish() as usize;
+        let secret = candidates[random % candidates.len()].to_owned();
+        Self::from_embeddings(&secret, scorer)
+    }
+
+    /// score of a word and its rank if it is among the closest words, None if the word is unknown
+    pub fn score(&self, word: &str) -> Option<CemantixWord> {
+        if let Some(w) = self.closest.get(word) {
+            return Some(CemantixWord::new(w.word.to_owned(), w.rank, w.score));
+        }
+        let score = self.scorer.as_ref()?.score(&self.secret, word)?;
+        Some(CemantixWord::new(word.to_owned(), 0, score))
+    }
+
+    /// closest words of the secret, the closest first
+    pub fn closest(&self) -> Vec<&CemantixWord> {
+        let mut closest = self.closest.values().collect::<Vec<&CemantixWord>>();
+        closest.sort_by(|a, b| b.rank.cmp(&a.rank).then(b.score.total_cmp(&a.score)));
+        closest
+    }
+}
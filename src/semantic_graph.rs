@@ -0,0 +1,267 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    options::options::{Cli, LogLevel},
+    words_getter::WordGetter,
+};
+
+/// link between a found word and one of its closest words
+#[derive(Debug, Clone, Serialize)]
+pub struct Edge {
+    pub source: usize,
+    pub target: usize,
+    pub score: f32,
+    pub rank: isize,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonNode<'a> {
+    id: usize,
+    word: &'a str,
+    answer: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonGraph<'a> {
+    nodes: Vec<JsonNode<'a>>,
+    edges: &'a [Edge],
+}
+
+/// graph whose nodes are words and whose edges go from a found word to its closest words
+#[derive(Debug, Default)]
+pub struct SemanticGraph {
+    pub nodes: Vec<String>,
+    pub edges: Vec<Edge>,
+    /// nodes which are words of the day
+    pub answers: HashSet<usize>,
+    indexes: HashMap<String, usize>,
+}
+
+impl SemanticGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// builds the graph from every file of the words directory
+    /// `limit` keeps only the closest words of each found word, the unreadable files are ignored
+    pub fn from_words_directory(
+        words_fcontainer_name: &str,
+        limit: Option<usize>,
+        cli: &Cli,
+    ) -> Result<Self> {
+        let mut graph = Self::new();
+        let mut found_words = WordGetter::get_all_found_word(words_fcontainer_name)?;
+        found_words.sort();
+        for found_word in found_words.iter() {
+            let mut words = match WordGetter::get_cemantix_words_of_found_word(
+                found_word,
+                words_fcontainer_name,
+            ) {
+                Ok(w) => w,
+                Err(e) => {
+                    cli.log_and_print(
+                        &format!("Ignoring {found_word} : {e}, try check --repair"),
+                        LogLevel::Warn,
+                    )?;
+                    continue;
+                }
+            };
+            words.sort();
+            words.reverse();
+            let source = graph.add_node(found_word);
+            graph.answers.insert(source);
+            for w in words
+                .iter()
+                .filter(|w| &w.word != found_word)
+                .take(limit.unwrap_or(usize::MAX))
+            {
+                graph.add_edge(found_word, &w.word, w.score, w.rank);
+            }
+        }
+        Ok(graph)
+    }
+
+    /// returns the index of the node, creating it if needed
+    pub fn add_node(&mut self, word: &str) -> usize {
+        if let Some(i) = self.indexes.get(word) {
+            return *i;
+        }
+        self.nodes.push(word.to_owned());
+        self.indexes.insert(word.to_owned(), self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, source: &str, target: &str, score: f32, rank: isize) {
+        let source = self.add_node(source);
+        let target = self.add_node(target);
+        self.edges.push(Edge {
+            source,
+            target,
+            score,
+            rank,
+        });
+    }
+
+    pub fn index(&self, word: &str) -> Option<usize> {
+        self.indexes.get(word).copied()
+    }
+
+    /// returns, for each node, its neighbours along with the score of the edge
+    /// edges are followed both ways
+    pub fn adjacency(&self) -> Vec<Vec<(usize, f32)>> {
+        let mut adjacency = vec![Vec::new(); self.nodes.len()];
+        for e in self.edges.iter() {
+            adjacency[e.source].push((e.target, e.score));
+            adjacency[e.target].push((e.source, e.score));
+        }
+        adjacency
+    }
+
+    /// finds the most similar chain of words between two nodes (Dijkstra on 1 - score)
+    /// returns every node of the path with the score of the edge that leads to it
+    pub fn shortest_path(&self, from: usize, to: usize) -> Option<Vec<(usize, f32)>> {
+        let adjacency = self.adjacency();
+        let mut costs = vec![f32::INFINITY; self.nodes.len()];
+        let mut previous: Vec<Option<(usize, f32)>> = vec![None; self.nodes.len()];
+        let mut heap = BinaryHeap::new();
+        costs[from] = 0.0;
+        heap.push(State {
+            cost: 0.0,
+            node: from,
+        });
+
+        while let Some(State { cost, node }) = heap.pop() {
+            if node == to {
+                break;
+            }
+            if cost > costs[node] {
+                continue;
+            }
+            for &(next, score) in adjacency[node].iter() {
+                let next_cost = cost + (1.0 - score).max(0.0);
+                if next_cost < costs[next] {
+                    costs[next] = next_cost;
+                    previous[next] = Some((node, score));
+                    heap.push(State {
+                        cost: next_cost,
+                        node: next,
+                    });
+                }
+            }
+        }
+
+        if costs[to].is_infinite() {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut node = to;
+        while let Some((p, score)) = previous[node] {
+            path.push((node, score));
+            node = p;
+        }
+        path.push((from, 1.0));
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut dot = String::from("digraph cemantix {\n");
+        for (i, word) in self.nodes.iter().enumerate() {
+            let shape = if self.answers.contains(&i) {
+                ", shape=box"
+            } else {
+                ""
+            };
+            dot += &format!("  n{i} [label=\"{}\"{shape}];\n", escape(word));
+        }
+        // dot only takes non negative integer weights
+        for e in self.edges.iter() {
+            dot += &format!(
+                "  n{} -> n{} [weight={}, label=\"{}\"];\n",
+                e.source,
+                e.target,
+                (e.score * 100.0).round().max(0.0) as i64,
+                e.rank
+            );
+        }
+        dot += "}\n";
+        dot
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        };
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n\
+             \x20 <key id=\"word\" for=\"node\" attr.name=\"word\" attr.type=\"string\"/>\n\
+             \x20 <key id=\"answer\" for=\"node\" attr.name=\"answer\" attr.type=\"boolean\"/>\n\
+             \x20 <key id=\"score\" for=\"edge\" attr.name=\"score\" attr.type=\"double\"/>\n\
+             \x20 <key id=\"rank\" for=\"edge\" attr.name=\"rank\" attr.type=\"int\"/>\n\
+             \x20 <graph id=\"cemantix\" edgedefault=\"directed\">\n",
+        );
+        for (i, word) in self.nodes.iter().enumerate() {
+            xml += &format!(
+                "    <node id=\"n{i}\"><data key=\"word\">{}</data><data key=\"answer\">{}</data></node>\n",
+                escape(word),
+                self.answers.contains(&i)
+            );
+        }
+        for (i, e) in self.edges.iter().enumerate() {
+            xml += &format!(
+                "    <edge id=\"e{i}\" source=\"n{}\" target=\"n{}\"><data key=\"score\">{}</data><data key=\"rank\">{}</data></edge>\n",
+                e.source, e.target, e.score, e.rank
+            );
+        }
+        xml += "  </graph>\n</graphml>\n";
+        xml
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&JsonGraph {
+            nodes: self
+                .nodes
+                .iter()
+                .enumerate()
+                .map(|(id, word)| JsonNode {
+                    id,
+                    word,
+                    answer: self.answers.contains(&id),
+                })
+                .collect(),
+            edges: &self.edges,
+        })?)
+    }
+}
+
+/// node waiting to be visited, the lowest cost comes first
+#[derive(Debug, PartialEq)]
+struct State {
+    cost: f32,
+    node: usize,
+}
+
+impl Eq for State {}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
@@ -0,0 +1,44 @@
+use std::{
+    fs::{self, read_to_string},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// new best word found while solving
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Guess {
+    /// time elapsed since the beginning of the session
+    pub elapsed_ms: i64,
+    pub word: String,
+    pub score: f32,
+}
+
+/// record of the resolution of a word of the day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub word: String,
+    /// day of the word, in the format of the history
+    pub date: String,
+    pub duration_ms: i64,
+    /// number of words sent to the game
+    pub requests: usize,
+    pub timeline: Vec<Guess>,
+}
+
+impl Session {
+    pub fn save(&self, sessions_directory: &str) -> Result<()> {
+        fs::create_dir_all(sessions_directory)?;
+        Ok(fs::write(
+            PathBuf::from(sessions_directory).join(&self.word),
+            serde_json::to_string(self)?,
+        )?)
+    }
+
+    pub fn load(word: &str, sessions_directory: &str) -> Result<Self> {
+        Ok(serde_json::from_str(&read_to_string(
+            PathBuf::from(sessions_directory).join(word),
+        )?)?)
+    }
+}
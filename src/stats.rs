@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+/// Jaccard similarity of two sorted slices without duplicates
+pub fn jaccard<T: Ord>(a: &[T], b: &[T]) -> f32 {
+    let (mut i, mut j, mut inter) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                inter += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    let union = a.len() + b.len() - inter;
+    if union == 0 {
+        return 0.0;
+    }
+    inter as f32 / union as f32
+}
+
+/// label propagation on a weighted undirected graph given as adjacency maps
+/// returns the label of each node, nodes with the same label belong to the same community
+pub fn label_propagation(links: &[HashMap<usize, f32>]) -> Vec<usize> {
+    let mut labels = (0..links.len()).collect::<Vec<usize>>();
+    let mut changed = true;
+    let mut iterations = 0;
+    while changed && iterations < 100 {
+        changed = false;
+        iterations += 1;
+        for i in 0..links.len() {
+            let mut weights: HashMap<usize, f32> = HashMap::new();
+            for (j, s) in links[i].iter() {
+                *weights.entry(labels[*j]).or_default() += s;
+            }
+            if let Some((label, _)) = weights
+                .into_iter()
+                .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+            {
+                if label != labels[i] {
+                    labels[i] = label;
+                    changed = true;
+                }
+            }
+        }
+    }
+    labels
+}
+
+/// counts the values falling in each of the `bins` intervals of equal width between `min` and `max`
+pub fn histogram(values: &[f32], bins: usize, min: f32, max: f32) -> Vec<usize> {
+    let mut counts = vec![0; bins];
+    if bins == 0 || max <= min {
+        return counts;
+    }
+    for v in values.iter() {
+        let bin = ((v - min) / (max - min) * bins as f32).floor();
+        counts[(bin.max(0.0) as usize).min(bins - 1)] += 1;
+    }
+    counts
+}
+
+pub fn mean(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f32>() / values.len() as f32
+}
+
+/// `p`th percentile (0 to 100) of sorted values, interpolated between the closest ranks
+pub fn percentile(sorted: &[f32], p: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let position = (p / 100.0).clamp(0.0, 1.0) * (sorted.len() - 1) as f32;
+    let (low, high) = (position.floor() as usize, position.ceil() as usize);
+    sorted[low] + (sorted[high] - sorted[low]) * (position - low as f32)
+}
+
+/// ranks of the values (1 for the lowest), tied values share their average rank
+pub fn ranks(values: &[f32]) -> Vec<f32> {
+    let mut order = (0..values.len()).collect::<Vec<usize>>();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && values[order[j + 1]] == values[order[i]] {
+            j += 1;
+        }
+        let rank = (i + j) as f32 / 2.0 + 1.0;
+        for k in i..=j {
+            ranks[order[k]] = rank;
+        }
+        i = j + 1;
+    }
+    ranks
+}
+
+/// Pearson correlation coefficient, None if a series is constant
+pub fn pearson(xs: &[f32], ys: &[f32]) -> Option<f32> {
+    let (mx, my) = (mean(xs), mean(ys));
+    let (mut cov, mut vx, mut vy) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys.iter()) {
+        cov += (x - mx) * (y - my);
+        vx += (x - mx) * (x - mx);
+        vy += (y - my) * (y - my);
+    }
+    (vx > 0.0 && vy > 0.0).then(|| cov / (vx * vy).sqrt())
+}
+
+/// Spearman rank correlation coefficient
+pub fn spearman(xs: &[f32], ys: &[f32]) -> Option<f32> {
+    pearson(&ranks(xs), &ranks(ys))
+}
+
+/// Kendall rank correlation coefficient (tau-b, accounting for ties)
+pub fn kendall(xs: &[f32], ys: &[f32]) -> Option<f32> {
+    let (mut concordant, mut discordant, mut ties_x, mut ties_y) = (0_i64, 0_i64, 0_i64, 0_i64);
+    for i in 0..xs.len() {
+        for j in (i + 1)..xs.len() {
+            let dx = xs[i].total_cmp(&xs[j]) as i64;
+            let dy = ys[i].total_cmp(&ys[j]) as i64;
+            match (dx, dy) {
+                (0, 0) => {}
+                (0, _) => ties_x += 1,
+                (_, 0) => ties_y += 1,
+                _ if dx == dy => concordant += 1,
+                _ => discordant += 1,
+            }
+        }
+    }
+    let n1 = (concordant + discordant + ties_x) as f32;
+    let n2 = (concordant + discordant + ties_y) as f32;
+    (n1 > 0.0 && n2 > 0.0).then(|| (concordant - discordant) as f32 / (n1 * n2).sqrt())
+}
+
+/// least squares fit of a polynomial, returns the coefficients from the constant term
+/// None if the points do not determine the polynomial
+pub fn polyfit(xs: &[f32], ys: &[f32], degree: usize) -> Option<Vec<f64>> {
+    let n = degree + 1;
+    // normal equations (X^T X) c = X^T y as an augmented matrix
+    let mut matrix = vec![vec![0.0_f64; n + 1]; n];
+    for (x, y) in xs.iter().zip(ys.iter()) {
+        let powers = (0..n)
+            .scan(1.0_f64, |p, _| {
+                let current = *p;
+                *p *= *x as f64;
+                Some(current)
+            })
+            .collect::<Vec<f64>>();
+        for i in 0..n {
+            for j in 0..n {
+                matrix[i][j] += powers[i] * powers[j];
+            }
+            matrix[i][n] += powers[i] * *y as f64;
+        }
+    }
+
+    // gaussian elimination with partial pivoting
+    for col in 0..n {
+        let pivot =
+            (col..n).max_by(|a, b| matrix[*a][col].abs().total_cmp(&matrix[*b][col].abs()))?;
+        if matrix[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        matrix.swap(col, pivot);
+        let pivot_row = matrix[col].clone();
+        for (row, values) in matrix.iter_mut().enumerate() {
+            if row != col {
+                let factor = values[col] / pivot_row[col];
+                for (v, p) in values.iter_mut().zip(pivot_row.iter()).skip(col) {
+                    *v -= factor * p;
+                }
+            }
+        }
+    }
+    Some((0..n).map(|i| matrix[i][n] / matrix[i][i]).collect())
+}
+
+/// root mean square and mean absolute errors
+pub fn errors(predictions: &[f32], targets: &[f32]) -> (f32, f32) {
+    if predictions.is_empty() {
+        return (0.0, 0.0);
+    }
+    let (squares, absolutes) = predictions
+        .iter()
+        .zip(targets.iter())
+        .fold((0.0, 0.0), |(s, a), (p, t)| {
+            (s + (p - t) * (p - t), a + (p - t).abs())
+        });
+    let n = predictions.len() as f32;
+    ((squares / n).sqrt(), absolutes / n)
+}
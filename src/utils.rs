@@ -1,11 +1,12 @@
 use anyhow::Result;
-use chrono::Local;
 use serde_json::Value;
 use std::{fs::OpenOptions, io::Write, sync::Arc};
 
 use futures::{lock::Mutex, Future};
 
 use crate::{
+    config::{Game, GameEndpoint},
+    day::puzzle_date,
     options::{
         options::{Cli, LogLevel},
         solve::{DataThread, Solve},
@@ -16,11 +17,11 @@ use crate::{
 pub async fn adding_word_to_historic(
     word: &str,
     word_history_filename: &str,
+    game: Game,
     cli: &Cli,
 ) -> Result<()> {
     // check if the word has already been found (file exists, so file is returned)
-    if WordGetter::get_last_found_word(word_history_filename)?
-        .is_some_and(|w| w.1 == Local::now().date_naive())
+    if WordGetter::get_last_found_word(word_history_filename)?.is_some_and(|w| w.1 == puzzle_date())
     {
         cli.log_and_print(
             &format!("Word already found, no need to register it"),
@@ -43,24 +44,21 @@ pub async fn adding_word_to_historic(
             return Err(anyhow::anyhow!(e));
         }
     };
-    let mut data_to_write: Vec<u8> = (word.to_owned()
-        + &" : ".to_string()
-        + &Local::now().format(crate::HISTORY_FORMAT).to_string())
-        .as_bytes()
-        .to_vec();
-    data_to_write.push(10);
-    file.write_all(&data_to_write)?;
+    let data_to_write = WordGetter::format_history_line(word, &puzzle_date(), game);
+    file.write_all(data_to_write.as_bytes())?;
 
     Ok(())
 }
 pub async fn send_words<T, F>(
+    endpoint: &GameEndpoint,
     iterator_len: usize,
     reader: T,
     batch_size: usize,
     best_word: Arc<Mutex<DataThread>>,
     callback: impl Fn(Arc<Mutex<DataThread>>, Vec<(String, Option<f32>)>) -> F,
     verbose: bool,
-) where
+) -> Result<()>
+where
     T: IntoIterator,
     T::Item: std::string::ToString,
     F: Future<Output = Result<bool>>,
@@ -81,7 +79,7 @@ pub async fn send_words<T, F>(
         if words_list.len() == 0 {
             break;
         }
-        let data = Solve::launch_threads_solve(words_list.clone()).await;
+        let data = Solve::launch_threads_solve(endpoint, words_list.clone()).await?;
         count += batch_size;
 
         let tmp = ((count as f32) / 3.0).floor() as usize;
@@ -99,24 +97,52 @@ pub async fn send_words<T, F>(
         };
         taken = iterator.by_ref().take(batch_size);
     }
+    Ok(())
 }
-pub fn generate_client(params: &[(&str, &str)]) -> reqwest::RequestBuilder {
+pub fn generate_client(
+    endpoint: &GameEndpoint,
+    params: &[(&str, &str)],
+) -> reqwest::RequestBuilder {
     let client = reqwest::Client::new();
     client
-        .post("https://cemantix.certitudes.org/score")
+        .post(endpoint.score_url())
         .form(&params)
         .header("Content-type", "application/x-www-form-urlencoded")
-        .header("Origin", "https://cemantix.certitudes.org")
+        .header("Origin", endpoint.origin())
+}
+
+/// sends a guess to Pedantix and returns the raw answer
+pub async fn send_pedantix_request(endpoint: &GameEndpoint, word: &str) -> Result<String> {
+    let client = reqwest::Client::new();
+    let params = [("word", word)];
+    let mut i = 0;
+    loop {
+        let response = client
+            .post(endpoint.pedantix_url())
+            .form(&params)
+            .header("Content-type", "application/x-www-form-urlencoded")
+            .header("Origin", endpoint.origin())
+            .send()
+            .await;
+        match response {
+            Ok(response) if response.status().is_success() => return Ok(response.text().await?),
+            Ok(response) => {
+                return Err(anyhow::anyhow!("Unexpected status : {}", response.status()))
+            }
+            Err(e) if i >= 5 => return Err(anyhow::anyhow!(e)),
+            Err(_) => i += 1,
+        }
+    }
 }
 
-pub async fn send_request(word: &str) -> Result<f32> {
+pub async fn send_request(endpoint: &GameEndpoint, word: &str) -> Result<f32> {
     let params = [("word", word)];
-    let mut a = generate_client(&params);
+    let mut a = generate_client(endpoint, &params);
     let mut i = 0;
     let mut response = a.send().await;
     while i < 5 && response.is_err() {
         i += 1;
-        a = generate_client(&params);
+        a = generate_client(endpoint, &params);
         response = a.send().await;
     }
     match response {
@@ -158,6 +184,9 @@ pub async fn send_request(word: &str) -> Result<f32> {
                 return Err(anyhow::anyhow!("None value"));
             }
         }
-        Err(_) => todo!(),
+        Err(e) => Err(anyhow::anyhow!(
+            "cannot reach {} : {e}",
+            endpoint.score_url()
+        )),
     }
 }
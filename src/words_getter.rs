@@ -7,7 +7,12 @@ use std::{
 use anyhow::Result;
 use chrono::NaiveDate;
 
-use crate::cemantix_word::CemantixWord;
+use crate::{
+    cemantix_word::CemantixWord,
+    config::Game,
+    day::puzzle_number,
+    options::options::{Cli, LogLevel},
+};
 pub struct WordGetter {}
 
 impl WordGetter {
@@ -103,6 +108,16 @@ impl WordGetter {
             .open(PathBuf::from(words_fcontainer_name).join(&word.clone()))?;
         return Ok(file);
     }
+    /// creates the file of a found word, or truncates it if it already exists
+    pub fn create_file_word(
+        word: &str,
+        words_fcontainer_name: &str,
+    ) -> Result<std::fs::File, std::io::Error> {
+        let file = Self::get_file_word(word, false, true, false, words_fcontainer_name)
+            .or_else(|_| Self::get_file_word(word, true, true, false, words_fcontainer_name))?;
+        file.set_len(0)?;
+        Ok(file)
+    }
     pub fn get_all_found_word(words_fcontainer_name: &str) -> Result<Vec<String>> {
         Ok(read_dir(words_fcontainer_name)?
             .map(|f| match f {
@@ -118,26 +133,160 @@ impl WordGetter {
             .collect::<Vec<String>>())
     }
     pub fn get_last_found_word(word_history_filename: &str) -> Result<Option<(String, NaiveDate)>> {
+        // nothing has been found yet in a game never played
+        if !PathBuf::from(word_history_filename).try_exists()? {
+            return Ok(None);
+        }
         let file = OpenOptions::new()
             .create(false)
             .read(true)
             .open(word_history_filename)?;
         let line = BufReader::new(file).lines().last();
         match line {
-            Some(l) => {
-                let line = l?;
-                let mut data = line.split(":");
-                let word = data.next().map(|v| v.trim().to_string());
-                let date = data
-                    .next()
-                    .map(|v| NaiveDate::parse_from_str(v.trim(), crate::HISTORY_FORMAT));
-                if let (Some(w), Some(d)) = (word, date) {
-                    Ok(Some((w, d?)))
-                } else {
-                    Err(anyhow::anyhow!("Invalid end of file"))
-                }
-            }
+            Some(l) => Ok(Self::parse_history_line(&l?).map(|(w, d, _)| Some((w, d)))?),
             None => Ok(None),
         }
     }
+    /// returns the word itself, or the word found at `day` if it is a date (dd-mm-yyyy)
+    pub fn resolve_day(day: &str, word_history_filename: &str) -> Result<String> {
+        match NaiveDate::parse_from_str(day, crate::HISTORY_FORMAT) {
+            Ok(date) => Self::get_history(word_history_filename)?
+                .into_iter()
+                .find(|(_, d)| *d == date)
+                .map(|(w, _)| w)
+                .ok_or_else(|| anyhow::anyhow!("No word found on {day}")),
+            Err(_) => Ok(day.to_owned()),
+        }
+    }
+    /// returns every (word, date) registered in the history, in the order of the file
+    pub fn get_history(word_history_filename: &str) -> Result<Vec<(String, NaiveDate)>> {
+        Self::parse_history(&read_to_string(word_history_filename)?)
+    }
+    /// parses the content of a history file
+    pub fn parse_history(content: &str) -> Result<Vec<(String, NaiveDate)>> {
+        content
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| Self::parse_history_line(l).map(|(w, d, _)| (w, d)))
+            .collect()
+    }
+    /// splits a history line on the separators which are not escaped, unescaping the fields
+    fn split_history_line(line: &str) -> Vec<String> {
+        let mut fields = vec![String::new()];
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => fields.last_mut().unwrap().extend(chars.next()),
+                ':' => fields.push(String::new()),
+                c => fields.last_mut().unwrap().push(c),
+            }
+        }
+        fields
+    }
+    /// parses "word : date : puzzle number", the number being absent from the old lines
+    /// the date is trusted, the check command reports the numbers that do not match it
+    pub fn parse_history_line(line: &str) -> Result<(String, NaiveDate, Option<i64>)> {
+        let fields = Self::split_history_line(line);
+        let (word, date) = match &fields[..] {
+            [word, date] | [word, date, _] => (word.trim().to_owned(), date.trim()),
+            _ => return Err(anyhow::anyhow!("Invalid history line : {line}")),
+        };
+        let date = NaiveDate::parse_from_str(date, crate::HISTORY_FORMAT)?;
+        let number = match fields.get(2) {
+            Some(number) => Some(
+                number
+                    .trim()
+                    .parse::<i64>()
+                    .map_err(|_| anyhow::anyhow!("Invalid puzzle number : {line}"))?,
+            ),
+            None => None,
+        };
+        Ok((word, date, number))
+    }
+    /// formats a line of the history, the separators inside the word being escaped
+    pub fn format_history_line(word: &str, date: &NaiveDate, game: Game) -> String {
+        format!(
+            "{} : {} : {}\n",
+            word.replace('\\', "\\\\").replace(':', "\\:"),
+            date.format(crate::HISTORY_FORMAT),
+            puzzle_number(game, *date)
+        )
+    }
+    /// overwrites the history with `history`, sorted by date
+    pub fn save_history(
+        word_history_filename: &str,
+        history: &[(String, NaiveDate)],
+        game: Game,
+    ) -> Result<()> {
+        let mut sorted = history.iter().collect::<Vec<_>>();
+        sorted.sort_by_key(|(_, d)| *d);
+        let data = sorted
+            .iter()
+            .map(|(w, d)| Self::format_history_line(w, d, game))
+            .collect::<String>();
+        Ok(fs::write(word_history_filename, data)?)
+    }
+    /// adds the entries of `other` to `history`, a date can only have one word
+    /// returns the conflicting entries of `other` which have not been added
+    pub fn merge_history(
+        history: &mut Vec<(String, NaiveDate)>,
+        other: Vec<(String, NaiveDate)>,
+    ) -> Vec<(String, NaiveDate)> {
+        let mut conflicts = Vec::new();
+        for (word, date) in other {
+            match history.iter().find(|(_, d)| *d == date) {
+                Some((w, _)) if *w == word => {}
+                Some(_) => conflicts.push((word, date)),
+                None => history.push((word, date)),
+            }
+        }
+        history.sort_by_key(|(_, d)| *d);
+        conflicts
+    }
+    /// merges `other` into the history of the working directory and reports the conflicts,
+    /// `origin` naming where `other` comes from ; returns the number of added days and of conflicts
+    pub fn merge_history_file(
+        other: Vec<(String, NaiveDate)>,
+        origin: &str,
+        cli: &Cli,
+    ) -> Result<(usize, usize)> {
+        let mut history = if PathBuf::from(&cli.word_history).try_exists()? {
+            Self::get_history(&cli.word_history)?
+        } else {
+            Vec::new()
+        };
+        let len = history.len();
+        let conflicts = Self::merge_history(&mut history, other);
+        for (word, date) in conflicts.iter() {
+            let local = history
+                .iter()
+                .find(|(_, d)| d == date)
+                .map(|(w, _)| w.as_str())
+                .unwrap_or_default();
+            cli.log_and_print(
+                &format!(
+                    "Conflict on {} : {} (local) != {} ({}), keeping {}",
+                    date.format(crate::HISTORY_FORMAT),
+                    local,
+                    word,
+                    origin,
+                    local
+                ),
+                LogLevel::Warn,
+            )?;
+        }
+        Self::save_history(&cli.word_history, &history, cli.game)?;
+        Ok((history.len() - len, conflicts.len()))
+    }
+    /// overwrites the file of a found word with `words`, sorted by score
+    pub fn save_cemantix_words_of_found_word<'a>(
+        word: &str,
+        words: impl IntoIterator<Item = &'a CemantixWord>,
+        words_fcontainer_name: &str,
+    ) -> Result<()> {
+        let file = Self::create_file_word(word, words_fcontainer_name)?;
+        let mut copy = Vec::from_iter(words);
+        copy.sort();
+        Ok(serde_json::to_writer(file, &copy)?)
+    }
 }